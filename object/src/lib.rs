@@ -6,3 +6,21 @@ pub struct Object {
     /// The data in the object.
     pub data: bytes::Bytes,
 }
+
+#[cfg(feature = "dump")]
+impl storage_noodle_traits::dump::Archived for Object {
+    fn encode(&self) -> serde_json::Result<storage_noodle_traits::dump::Encoded> {
+        Ok(storage_noodle_traits::dump::Encoded::Blob(
+            self.data.to_vec(),
+        ))
+    }
+
+    fn decode(encoded: storage_noodle_traits::dump::Encoded) -> serde_json::Result<Self> {
+        match encoded {
+            storage_noodle_traits::dump::Encoded::Blob(bytes) => Ok(Self { data: bytes.into() }),
+            storage_noodle_traits::dump::Encoded::Record(_) => Err(serde::de::Error::custom(
+                "expected an object blob, found a record",
+            )),
+        }
+    }
+}
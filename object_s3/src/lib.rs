@@ -1,8 +1,19 @@
 //! Provides S3 support to `storage_noodle_object`.
 
-use minio::s3::types::S3Api;
+use minio::s3::types::{S3Api, ToStream};
 use storage_noodle_object::Object;
-use storage_noodle_traits::{Create, Delete, Read, Update};
+use storage_noodle_traits::{
+    Create, CreateWithId, CreateWithTtl, Delete, Items, List, Purge, Read, Restore, Update,
+    id::{IdGenerator, PrefixedRandom},
+    tenant::{ScopedCreate, ScopedDelete, ScopedRead, ScopedUpdate},
+};
+
+/// The user metadata key that holds the unix time (in seconds) that an object expires at.
+const EXPIRES_AT_METADATA: &str = "expires-at";
+
+/// The tag that holds the number of days an object lives for. Used to match objects in
+/// lifecycle rules, see [`S3Backing::ttl_lifecycle_rule`].
+pub const TTL_DAYS_TAG: &str = "storage-noodle-ttl-days";
 
 /// S3 backing storage - only supports single-bucket usage.
 pub struct S3Backing {
@@ -11,22 +22,327 @@ pub struct S3Backing {
 
     /// The bucket to use.
     pub bucket: String,
+
+    /// Deleted objects are moved under this prefix instead of being removed, so that they can be
+    /// restored later. [`None`] disables soft deletion.
+    pub trash_prefix: Option<String>,
+
+    /// Generates the ids (object keys) of new objects.
+    pub id_generator: Box<dyn IdGenerator<String> + Send + Sync>,
+}
+
+impl S3Backing {
+    /// Create a new instance, without soft deletion. Ids are 256 random bits, encoded as url-safe
+    /// base64.
+    #[must_use]
+    pub fn new(client: minio::s3::Client, bucket: String) -> Self {
+        Self {
+            client,
+            bucket,
+            trash_prefix: None,
+            id_generator: Box::new(PrefixedRandom::default()),
+        }
+    }
+
+    /// Use a different [`IdGenerator`] for new objects.
+    #[must_use]
+    pub fn with_id_generator(
+        mut self,
+        id_generator: impl IdGenerator<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.id_generator = Box::new(id_generator);
+        self
+    }
+
+    /// Creates a lifecycle rule that makes the bucket remove objects created with a TTL of `days`
+    /// days. Objects created with [`CreateWithTtl`] are treated as absent once they expire, so the
+    /// rule is only needed to reclaim the space.
+    ///
+    /// TTLs are rounded up to whole days, add a rule for each number of days in use.
+    #[must_use]
+    pub fn ttl_lifecycle_rule(days: u32) -> minio::s3::lifecycle_config::LifecycleRule {
+        minio::s3::lifecycle_config::LifecycleRule {
+            id: format!("{TTL_DAYS_TAG}-{days}"),
+            status: true,
+            filter: minio::s3::types::Filter {
+                and_operator: None,
+                prefix: None,
+                tag: Some(minio::s3::types::Tag {
+                    key: TTL_DAYS_TAG.to_string(),
+                    value: days.to_string(),
+                }),
+            },
+            expiration_days: Some(days),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the key that an object is moved to when it is soft deleted.
+    fn trash_key(&self, key: &str) -> Option<String> {
+        self.trash_prefix
+            .as_ref()
+            .map(|prefix| format!("{prefix}/{key}"))
+    }
 }
 
 impl storage_noodle_traits::BackingStorage for S3Backing {
     type RawId = String;
 }
 
-/// Generates a random ID.
-fn make_id() -> String {
-    use base64::{Engine as _, engine::general_purpose::URL_SAFE};
+/// Returns the object key of an item that belongs to a tenant.
+///
+/// Tenant ids and ids that are empty or contain `/` are rejected (with an error message), so that
+/// a tenant's keys can't reach under another tenant's prefix (e.g. tenant `a` and id `b/c` would
+/// be id `c` of tenant `a/b`).
+fn tenant_key(tenant: &impl core::fmt::Display, id: &str) -> Result<String, String> {
+    let tenant = tenant.to_string();
+    for part in [&tenant, id] {
+        if part.is_empty() || part.contains('/') {
+            return Err(format!(
+                "tenant ids and ids can't be empty or contain `/`: {part:?}"
+            ));
+        }
+    }
+    Ok(format!("{tenant}/{id}"))
+}
+
+/// Returns the current unix time in seconds.
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// Returns `true` if an object that expires at `expires_at` has expired.
+fn is_expired(expires_at: Option<u64>) -> bool {
+    expires_at.is_some_and(|expires_at| expires_at <= unix_now())
+}
+
+/// Returns `true` if the error is caused by a missing object.
+fn is_no_such_key(e: &minio::s3::error::Error) -> bool {
+    matches!(e, minio::s3::error::Error::S3Error(s3e) if s3e.code == minio::s3::error::ErrorCode::NoSuchKey)
+}
+
+/// Returns `true` if the error is caused by a failed precondition, such as an `If-None-Match`
+/// header matching an existing object.
+fn is_precondition_failed(e: &minio::s3::error::Error) -> bool {
+    match e {
+        // The client lowercases error codes it doesn't know.
+        minio::s3::error::Error::S3Error(s3e) => matches!(
+            &s3e.code,
+            minio::s3::error::ErrorCode::OtherError(code) if code.eq_ignore_ascii_case("PreconditionFailed")
+        ),
+        minio::s3::error::Error::ServerError(status) => *status == 412,
+        _ => false,
+    }
+}
+
+/// Uploads an object to a key. `expires_at` is the unix time (in seconds) that the object expires
+/// at.
+async fn put(
+    storage: &S3Backing,
+    key: String,
+    object: &Object,
+    expires_at: Option<u64>,
+) -> Result<(), minio::s3::error::Error> {
+    // Record the expiry time, and tag the object so that lifecycle rules can remove it.
+    let (user_metadata, tags) = expires_at.map_or((None, None), |expires_at| {
+        let mut user_metadata = minio::s3::multimap::Multimap::new();
+        user_metadata.insert(
+            format!("x-amz-meta-{EXPIRES_AT_METADATA}"),
+            expires_at.to_string(),
+        );
+
+        let days = expires_at.saturating_sub(unix_now()).div_ceil(24 * 60 * 60);
+        let tags = std::collections::HashMap::from([(TTL_DAYS_TAG.to_string(), days.to_string())]);
+
+        (Some(user_metadata), Some(tags))
+    });
+
+    storage
+        .client
+        .put_object(&storage.bucket, key, object.data.clone().into())
+        .user_metadata(user_metadata)
+        .tags(tags)
+        .send()
+        .await
+        .map(|_| ())
+}
+
+/// Creates an object under a new id. `key` maps the id to an object key.
+async fn create(
+    storage: &S3Backing,
+    object: &Object,
+    key: impl FnOnce(&str) -> Result<String, String>,
+    expires_at: Option<u64>,
+) -> Result<String, minio::s3::error::Error> {
+    // Generate ID.
+    let id = storage.id_generator.generate();
+
+    // Upload data.
+    //= traits/spec.md#create-trait
+    //# * In the case of a failure, the future MUST return `Err()`.
+    let key = key(&id).map_err(minio::s3::error::Error::InvalidObjectName)?;
+    put(storage, key, object, expires_at).await?;
+
+    //= traits/spec.md#create-trait
+    //# * In the case of a success, the future MUST return `Ok(AssocId<Self, RawId>)` - where the `AssocId` holds the Id of the newly created item.
+    Ok(id)
+}
+
+/// Reads the object at a key.
+async fn read(storage: &S3Backing, key: &str) -> Result<Option<Object>, minio::s3::error::Error> {
+    // Get data.
+    let result = storage.client.get_object(&storage.bucket, key).send().await;
+
+    // Return Ok(None) if the object doesn't exist.
+    match result {
+        Ok(response) => {
+            // Expired objects are treated as absent.
+            let expires_at = response
+                .headers
+                .get(format!("x-amz-meta-{EXPIRES_AT_METADATA}"))
+                .and_then(|value| value.to_str().ok()?.parse().ok());
+            if is_expired(expires_at) {
+                //= traits/spec.md#expiry-traits
+                //# Once an item has expired, it MUST be treated as if it doesn't exist by the [`Read`](#read-trait) and [`Update`](#update-trait) traits.
+                return Ok(None);
+            }
+
+            // FIXME: using `to_bytes` is not optimal.
+            let segmented_bytes = response.content.to_segmented_bytes().await?;
+            let bytes = segmented_bytes.to_bytes();
+
+            //= traits/spec.md#read-trait
+            //# * In the case of a full success, the future MUST return `Ok(Some(Self))` - where `Self` is the result of the read.
+            Ok(Some(Object { data: bytes }))
+        }
+        Err(e) => {
+            if is_no_such_key(&e) {
+                //= traits/spec.md#read-trait
+                //# * In the case of a partial success, where the operation succeeded, but the item doesn't exist, the future MUST return `Ok(None)`.
+                Ok(None)
+            } else {
+                //= traits/spec.md#read-trait
+                //# * In the case of a failure, the future MUST return `Err()`.
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Overwrites the object at a key, if it exists.
+async fn update(
+    storage: &S3Backing,
+    key: String,
+    object: &Object,
+) -> Result<Option<()>, minio::s3::error::Error> {
+    // Check that the object does exist
+    let stat = match storage
+        .client
+        .stat_object(&storage.bucket, &key)
+        .send()
+        .await
+    {
+        Ok(stat) => stat,
+        Err(e) => {
+            if is_no_such_key(&e) {
+                //= traits/spec.md#update-trait
+                //# * In the case of a partial success, where the operation succeeded, but the item doesn't exist, the future MUST return `Ok(None)`.
+                return Ok(None);
+            }
+            //= traits/spec.md#update-trait
+            //# * In the case of a failure, the future MUST return `Err()`.
+            return Err(e);
+        }
+    };
+
+    // Expired objects are treated as absent, the expiry of live objects is kept.
+    let expires_at = stat
+        .user_metadata
+        .get(EXPIRES_AT_METADATA)
+        .and_then(|value| value.parse().ok());
+    if is_expired(expires_at) {
+        //= traits/spec.md#expiry-traits
+        //# Once an item has expired, it MUST be treated as if it doesn't exist by the [`Read`](#read-trait) and [`Update`](#update-trait) traits.
+        return Ok(None);
+    }
+
+    // Upload data.
+    //= traits/spec.md#update-trait
+    //# * In the case of a failure, the future MUST return `Err()`.
+    put(storage, key, object, expires_at).await?;
+
+    //= traits/spec.md#update-trait
+    //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
+    Ok(Some(()))
+}
+
+/// Moves an object to a different key. Returns [`None`] if there is no object at `from`.
+async fn rename(
+    storage: &S3Backing,
+    from: &str,
+    to: &str,
+) -> Result<Option<()>, minio::s3::error::Error> {
+    let source = minio::s3::builders::CopySource::new(&storage.bucket, from)?;
+    let result = storage
+        .client
+        .copy_object(&storage.bucket, to)
+        .source(source)
+        .send()
+        .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) if is_no_such_key(&e) => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    storage
+        .client
+        .delete_object(&storage.bucket, from)
+        .send()
+        .await
+        .map(|_| Some(()))
+}
+
+/// Deletes the object at a key. The object is moved to the trash if soft deletion is enabled.
+async fn delete(storage: &S3Backing, key: &str) -> Result<Option<()>, minio::s3::error::Error> {
+    // Move the object to the trash.
+    if let Some(trash_key) = storage.trash_key(key) {
+        //= traits/spec.md#delete-trait
+        //# * In the case of a failure, the future MUST return `Err()`.
+        //= traits/spec.md#delete-trait
+        //# * In the case of a partial success, where the operation succeeded, but the item doesn't exist, the future MUST return `Ok(None)`.
+        //= traits/spec.md#delete-trait
+        //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
+        return rename(storage, key, &trash_key).await;
+    }
 
-    let random: [u8; 32] = rand::random();
-    // 256 bit random number.
+    // Delete the object.
+    let result = storage
+        .client
+        .delete_object(&storage.bucket, key)
+        .send()
+        .await;
 
-    // Use url-safe characters to encode the
-    // random number.
-    URL_SAFE.encode(random)
+    // Return Ok(None) if the object doesn't exist.
+    match result {
+        //= traits/spec.md#delete-trait
+        //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
+        Ok(_) => Ok(Some(())),
+        Err(e) => {
+            if is_no_such_key(&e) {
+                //= traits/spec.md#delete-trait
+                //# * In the case of a partial success, where the operation succeeded, but the item doesn't exist, the future MUST return `Ok(None)`.
+                Ok(None)
+            } else {
+                //= traits/spec.md#delete-trait
+                //# * In the case of a failure, the future MUST return `Err()`.
+                Err(e)
+            }
+        }
+    }
 }
 
 impl Create<S3Backing> for Object {
@@ -42,21 +358,79 @@ impl Create<S3Backing> for Object {
         >,
         Self::Error,
     > {
-        // Generate ID.
-        let name = make_id();
+        create(&storage, self, |id| Ok(id.to_string()), None)
+            .await
+            .map(storage_noodle_traits::AssocId::new)
+    }
+}
 
-        // Upload data.
-        let result = storage
-            .client
-            .put_object(&storage.bucket, name, self.data.clone().into())
-            .send()
-            .await;
+impl CreateWithTtl<S3Backing> for Object {
+    type Error = minio::s3::error::Error;
+
+    async fn create_with_ttl<'a>(
+        &'a self,
+        storage: impl core::ops::Deref<Target = S3Backing> + 'a + Send,
+        ttl: core::time::Duration,
+    ) -> Result<
+        storage_noodle_traits::AssocId<
+            Self,
+            <S3Backing as storage_noodle_traits::BackingStorage>::RawId,
+        >,
+        Self::Error,
+    > {
+        let expires_at = unix_now().saturating_add(ttl.as_secs());
 
-        //= traits/spec.md#create-trait
+        //= traits/spec.md#create-with-ttl-trait
         //# * In the case of a failure, the future MUST return `Err()`.
-        //= traits/spec.md#create-trait
+        //= traits/spec.md#create-with-ttl-trait
         //# * In the case of a success, the future MUST return `Ok(AssocId<Self, RawId>)` - where the `AssocId` holds the Id of the newly created item.
-        result.map(|response| storage_noodle_traits::AssocId::new(response.object))
+        create(&storage, self, |id| Ok(id.to_string()), Some(expires_at))
+            .await
+            .map(storage_noodle_traits::AssocId::new)
+    }
+}
+
+impl CreateWithId<S3Backing> for Object {
+    type Error = minio::s3::error::Error;
+
+    async fn create_with_id<'a>(
+        &'a self,
+        storage: impl core::ops::Deref<Target = S3Backing> + 'a + Send,
+        id: impl core::ops::Deref<
+            Target = storage_noodle_traits::AssocId<
+                Self,
+                <S3Backing as storage_noodle_traits::BackingStorage>::RawId,
+            >,
+        >
+        + 'a
+        + Send,
+    ) -> Result<Option<()>, Self::Error> {
+        // Only upload the data if the key is free, so that concurrent creates can't overwrite
+        // each other.
+        let mut extra_headers = minio::s3::multimap::Multimap::new();
+        extra_headers.insert("If-None-Match".to_string(), "*".to_string());
+
+        match storage
+            .client
+            .put_object(
+                &storage.bucket,
+                id.as_raw().clone(),
+                self.data.clone().into(),
+            )
+            .extra_headers(Some(extra_headers))
+            .send()
+            .await
+        {
+            //= traits/spec.md#create-with-id-trait
+            //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
+            Ok(_) => Ok(Some(())),
+            //= traits/spec.md#create-with-id-trait
+            //# * In the case of a partial success, where the operation succeeded, but an item with the id already exists, the future MUST return `Ok(None)` and leave the existing item unchanged.
+            Err(e) if is_precondition_failed(&e) => Ok(None),
+            //= traits/spec.md#create-with-id-trait
+            //# * In the case of a failure, the future MUST return `Err()`.
+            Err(e) => Err(e),
+        }
     }
 }
 
@@ -72,38 +446,7 @@ impl Read<S3Backing> for Object {
             >,
         > + Send,
     ) -> Result<Option<Self>, Self::Error> {
-        // Get data.
-        let result = storage
-            .client
-            .get_object(&storage.bucket, id.as_raw())
-            .send()
-            .await;
-
-        // Return Ok(None) if the object doesn't exist.
-        match result {
-            Ok(response) => {
-                // FIXME: using `to_bytes` is not optimal.
-                let segmented_bytes = response.content.to_segmented_bytes().await?;
-                let bytes = segmented_bytes.to_bytes();
-
-                //= traits/spec.md#read-trait
-                //# * In the case of a full success, the future MUST return `Ok(Some(Self))` - where `Self` is the result of the read.
-                Ok(Some(Self { data: bytes }))
-            }
-            Err(e) => {
-                if let minio::s3::error::Error::S3Error(s3e) = &e
-                    && let minio::s3::error::ErrorCode::NoSuchKey = s3e.code
-                {
-                    //= traits/spec.md#read-trait
-                    //# * In the case of a partial success, where the operation succeeded, but the item doesn't exist, the future MUST return `Ok(None)`.
-                    Ok(None)
-                } else {
-                    //= traits/spec.md#read-trait
-                    //# * In the case of a failure, the future MUST return `Err()`.
-                    Err(e)
-                }
-            }
-        }
+        read(&storage, id.as_raw()).await
     }
 }
 
@@ -120,47 +463,96 @@ impl Update<S3Backing> for Object {
             >,
         > + Send,
     ) -> Result<Option<()>, Self::Error> {
-        // Check that the object does exist
-        if let Err(e) = storage
+        update(&storage, id.as_raw().clone(), self).await
+    }
+}
+
+impl Delete<S3Backing> for Object {
+    type Error = minio::s3::error::Error;
+
+    async fn delete(
+        storage: impl core::ops::Deref<Target = S3Backing> + Send,
+        id: impl core::ops::Deref<
+            Target = storage_noodle_traits::AssocId<
+                Self,
+                <S3Backing as storage_noodle_traits::BackingStorage>::RawId,
+            >,
+        > + Send,
+    ) -> Result<Option<()>, Self::Error> {
+        delete(&storage, id.as_raw()).await
+    }
+}
+
+impl List<S3Backing> for Object {
+    type Error = minio::s3::error::Error;
+
+    async fn list(
+        storage: impl core::ops::Deref<Target = S3Backing> + Send,
+    ) -> Result<Items<Self, <S3Backing as storage_noodle_traits::BackingStorage>::RawId>, Self::Error>
+    {
+        // Only list the top level of the bucket, tenant scoped and trashed objects are under a
+        // prefix.
+        let mut pages = storage
             .client
-            .get_object(&storage.bucket, id.deref().as_raw())
-            .send()
-            .await
-            && let minio::s3::error::Error::S3Error(s3e) = &e
-        {
-            if let minio::s3::error::ErrorCode::NoSuchKey = s3e.code {
-                //= traits/spec.md#update-trait
-                //# * In the case of a partial success, where the operation succeeded, but the item doesn't exist, the future MUST return `Ok(None)`.
-                return Ok(None);
-            }
-            //= traits/spec.md#update-trait
+            .list_objects(&storage.bucket)
+            .recursive(false)
+            .to_stream()
+            .await;
+
+        let mut items = Vec::new();
+        while let Some(page) = futures_util::StreamExt::next(&mut pages).await {
+            //= traits/spec.md#list-trait
             //# * In the case of a failure, the future MUST return `Err()`.
-            return Err(e);
+            for entry in page?.contents {
+                if entry.is_prefix {
+                    continue;
+                }
+
+                // Objects that expired (or were deleted since they were listed) are skipped.
+                if let Some(object) = read(&storage, &entry.name).await? {
+                    items.push((storage_noodle_traits::AssocId::new(entry.name), object));
+                }
+            }
         }
 
-        // Upload data.
-        let result = storage
-            .client
-            .put_object(
-                &storage.bucket,
-                id.deref().as_raw(),
-                self.data.clone().into(),
-            )
-            .send()
-            .await;
+        //= traits/spec.md#list-trait
+        //# * In the case of a success, the future MUST return `Ok(Vec<(AssocId<Self, RawId>, Self)>)` - where the `Vec` holds every item that the [`Read`](#read-trait) trait would return, along with its Id.
+        Ok(items)
+    }
+}
+
+impl Restore<S3Backing> for Object {
+    type Error = minio::s3::error::Error;
+
+    async fn restore(
+        storage: impl core::ops::Deref<Target = S3Backing> + Send,
+        id: impl core::ops::Deref<
+            Target = storage_noodle_traits::AssocId<
+                Self,
+                <S3Backing as storage_noodle_traits::BackingStorage>::RawId,
+            >,
+        > + Send,
+    ) -> Result<Option<()>, Self::Error> {
+        let Some(trash_key) = storage.trash_key(id.as_raw()) else {
+            //= traits/spec.md#restore-trait
+            //# * In the case of a partial success, where the operation succeeded, but there is no deleted item with the id, the future MUST return `Ok(None)`.
+            return Ok(None);
+        };
 
-        //= traits/spec.md#update-trait
+        //= traits/spec.md#restore-trait
         //# * In the case of a failure, the future MUST return `Err()`.
-        //= traits/spec.md#update-trait
+        //= traits/spec.md#restore-trait
+        //# * In the case of a partial success, where the operation succeeded, but there is no deleted item with the id, the future MUST return `Ok(None)`.
+        //= traits/spec.md#restore-trait
         //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
-        result.map(|_| Some(()))
+        rename(&storage, &trash_key, id.as_raw()).await
     }
 }
 
-impl Delete<S3Backing> for Object {
+impl Purge<S3Backing> for Object {
     type Error = minio::s3::error::Error;
 
-    async fn delete(
+    async fn purge(
         storage: impl core::ops::Deref<Target = S3Backing> + Send,
         id: impl core::ops::Deref<
             Target = storage_noodle_traits::AssocId<
@@ -169,31 +561,110 @@ impl Delete<S3Backing> for Object {
             >,
         > + Send,
     ) -> Result<Option<()>, Self::Error> {
-        // Delete the object.
-        let result = storage
+        let Some(trash_key) = storage.trash_key(id.as_raw()) else {
+            //= traits/spec.md#purge-trait
+            //# * In the case of a partial success, where the operation succeeded, but there is no deleted item with the id, the future MUST return `Ok(None)`.
+            return Ok(None);
+        };
+
+        // Check that the object is in the trash, S3 doesn't report deletes of missing objects.
+        if let Err(e) = storage
             .client
-            .delete_object(&storage.bucket, id.deref().as_raw())
+            .stat_object(&storage.bucket, &trash_key)
             .send()
-            .await;
-
-        // Return Ok(None) if the object doesn't exist.
-        match result {
-            //= traits/spec.md#delete-trait
-            //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
-            Ok(_) => Ok(Some(())),
-            Err(e) => {
-                if let minio::s3::error::Error::S3Error(s3e) = &e
-                    && let minio::s3::error::ErrorCode::NoSuchKey = s3e.code
-                {
-                    //= traits/spec.md#delete-trait
-                    //# * In the case of a partial success, where the operation succeeded, but the item doesn't exist, the future MUST return `Ok(None)`.
-                    Ok(None)
-                } else {
-                    //= traits/spec.md#delete-trait
-                    //# * In the case of a failure, the future MUST return `Err()`.
-                    Err(e)
-                }
+            .await
+        {
+            if is_no_such_key(&e) {
+                //= traits/spec.md#purge-trait
+                //# * In the case of a partial success, where the operation succeeded, but there is no deleted item with the id, the future MUST return `Ok(None)`.
+                return Ok(None);
             }
+            //= traits/spec.md#purge-trait
+            //# * In the case of a failure, the future MUST return `Err()`.
+            return Err(e);
         }
+
+        //= traits/spec.md#purge-trait
+        //# * In the case of a failure, the future MUST return `Err()`.
+        //= traits/spec.md#purge-trait
+        //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
+        storage
+            .client
+            .delete_object(&storage.bucket, trash_key)
+            .send()
+            .await
+            .map(|_| Some(()))
+    }
+}
+
+impl<TenantId: core::fmt::Display + Sync> ScopedCreate<S3Backing, TenantId> for Object {
+    type Error = minio::s3::error::Error;
+
+    async fn create_scoped<'a>(
+        &'a self,
+        storage: &'a S3Backing,
+        tenant: &'a TenantId,
+    ) -> Result<
+        storage_noodle_traits::AssocId<
+            Self,
+            <S3Backing as storage_noodle_traits::BackingStorage>::RawId,
+        >,
+        Self::Error,
+    > {
+        create(storage, self, |id| tenant_key(tenant, id), None)
+            .await
+            .map(storage_noodle_traits::AssocId::new)
+    }
+}
+
+impl<TenantId: core::fmt::Display + Sync> ScopedRead<S3Backing, TenantId> for Object {
+    type Error = minio::s3::error::Error;
+
+    async fn read_scoped<'a>(
+        storage: &'a S3Backing,
+        tenant: &'a TenantId,
+        id: &'a storage_noodle_traits::AssocId<
+            Self,
+            <S3Backing as storage_noodle_traits::BackingStorage>::RawId,
+        >,
+    ) -> Result<Option<Self>, Self::Error> {
+        let key =
+            tenant_key(tenant, id.as_raw()).map_err(minio::s3::error::Error::InvalidObjectName)?;
+        read(storage, &key).await
+    }
+}
+
+impl<TenantId: core::fmt::Display + Sync> ScopedUpdate<S3Backing, TenantId> for Object {
+    type Error = minio::s3::error::Error;
+
+    async fn update_scoped<'a>(
+        &'a self,
+        storage: &'a S3Backing,
+        tenant: &'a TenantId,
+        id: &'a storage_noodle_traits::AssocId<
+            Self,
+            <S3Backing as storage_noodle_traits::BackingStorage>::RawId,
+        >,
+    ) -> Result<Option<()>, Self::Error> {
+        let key =
+            tenant_key(tenant, id.as_raw()).map_err(minio::s3::error::Error::InvalidObjectName)?;
+        update(storage, key, self).await
+    }
+}
+
+impl<TenantId: core::fmt::Display + Sync> ScopedDelete<S3Backing, TenantId> for Object {
+    type Error = minio::s3::error::Error;
+
+    async fn delete_scoped<'a>(
+        storage: &'a S3Backing,
+        tenant: &'a TenantId,
+        id: &'a storage_noodle_traits::AssocId<
+            Self,
+            <S3Backing as storage_noodle_traits::BackingStorage>::RawId,
+        >,
+    ) -> Result<Option<()>, Self::Error> {
+        let key =
+            tenant_key(tenant, id.as_raw()).map_err(minio::s3::error::Error::InvalidObjectName)?;
+        delete(storage, &key).await
     }
 }
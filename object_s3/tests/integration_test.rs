@@ -5,11 +5,24 @@
 use minio::s3::types::S3Api;
 use storage_noodle_object::Object;
 use storage_noodle_object_s3::S3Backing;
-use storage_noodle_traits::{Create, Delete, Read, Update};
+use storage_noodle_traits::{
+    Create, CreateWithId, CreateWithTtl, Delete, List, Purge, Read, Restore, Storable, Update,
+    blocking::{self, Blocking},
+    dump::{Archive, dump, restore},
+    migrate::copy_all,
+    tenant::TenantScoped,
+};
 
 mod utils;
 
+/// Objects implement every CRUD trait for S3.
+const _: fn() = || {
+    fn storable<T: Storable<S3Backing>>() {}
+    storable::<Object>();
+};
+
 const BUCKET: &str = "integration-test";
+const OTHER_BUCKET: &str = "integration-test-copy";
 const ADDR: &str = "http://127.0.0.1:9000";
 
 #[tokio::test(flavor = "multi_thread")]
@@ -23,16 +36,15 @@ async fn main() {
     let client =
         minio::s3::Client::new(base_url, Some(Box::new(static_provider)), None, None).unwrap();
 
-    // Create bucket.
-    if !client.bucket_exists(BUCKET).send().await.unwrap().exists {
-        client.create_bucket(BUCKET).send().await.unwrap();
+    // Create buckets.
+    for bucket in [BUCKET, OTHER_BUCKET] {
+        if !client.bucket_exists(bucket).send().await.unwrap().exists {
+            client.create_bucket(bucket).send().await.unwrap();
+        }
     }
 
     // Create backing storage.
-    let backing = S3Backing {
-        client,
-        bucket: BUCKET.into(),
-    };
+    let mut backing = S3Backing::new(client, BUCKET.into());
 
     // Create an object.
     let object = Object {
@@ -61,4 +73,184 @@ async fn main() {
 
     // Assert that the object does not exist.
     assert_eq!(None, Object::read(&backing, &id).await.unwrap());
+
+    // Objects can be created with a known id, but not over an existing one.
+    let id = storage_noodle_traits::AssocId::new("known".to_string());
+    let object = Object {
+        data: "known".into(),
+    };
+    object.create_with_id(&backing, &id).await.unwrap().unwrap();
+    assert_eq!(None, object.create_with_id(&backing, &id).await.unwrap());
+    assert_eq!(Some(object), Object::read(&backing, &id).await.unwrap());
+
+    // Ids can be generated differently.
+    let prefixed = S3Backing::new(backing.client.clone(), BUCKET.into()).with_id_generator(
+        storage_noodle_traits::id::PrefixedRandom::new("upload-".to_string(), 16),
+    );
+    let id = Object {
+        data: "prefixed".into(),
+    }
+    .create(&prefixed)
+    .await
+    .unwrap();
+    assert!(id.as_raw().starts_with("upload-"));
+
+    // Every object can be copied to another bucket, or dumped to an archive (as blobs) and
+    // restored from it.
+    copy_to_other_bucket(&backing, &id).await;
+    dump_to_other_bucket(&backing, &id).await;
+
+    // Let the bucket clean up objects with a TTL of one day.
+    backing
+        .client
+        .put_bucket_lifecycle(BUCKET)
+        .life_cycle_config(minio::s3::lifecycle_config::LifecycleConfig {
+            rules: vec![S3Backing::ttl_lifecycle_rule(1)],
+        })
+        .send()
+        .await
+        .unwrap();
+
+    // Objects with a TTL can be read until they expire.
+    let object = Object {
+        data: "session".into(),
+    };
+    let id = object
+        .create_with_ttl(&backing, core::time::Duration::from_secs(60 * 60))
+        .await
+        .unwrap();
+    assert_eq!(Some(object), Object::read(&backing, &id).await.unwrap());
+
+    // Expired objects are treated as absent.
+    let object = Object {
+        data: "expired".into(),
+    };
+    let id = object
+        .create_with_ttl(&backing, core::time::Duration::ZERO)
+        .await
+        .unwrap();
+    assert_eq!(None, Object::read(&backing, &id).await.unwrap());
+    assert_eq!(None, object.update(&backing, &id).await.unwrap());
+
+    // The blocking API works outside of a runtime.
+    let client = backing.client.clone();
+    std::thread::spawn(move || blocking_round_trip(client))
+        .join()
+        .unwrap();
+
+    // Enable soft deletion.
+    backing.trash_prefix = Some("trash".to_string());
+
+    // Upload an object, then soft delete it.
+    let object = Object {
+        data: "vanilla".into(),
+    };
+    let id = object.create(&backing).await.unwrap();
+    Object::delete(&backing, &id).await.unwrap().unwrap();
+    assert_eq!(None, Object::read(&backing, &id).await.unwrap());
+
+    // Restore the object.
+    Object::restore(&backing, &id).await.unwrap().unwrap();
+    assert_eq!(Some(object), Object::read(&backing, &id).await.unwrap());
+
+    // Delete and purge the object, it can't be restored anymore.
+    Object::delete(&backing, &id).await.unwrap().unwrap();
+    Object::purge(&backing, &id).await.unwrap().unwrap();
+    assert_eq!(None, Object::restore(&backing, &id).await.unwrap());
+
+    // Scope the backing storage to a tenant.
+    let tenant = TenantScoped::new(backing, "alice".to_string());
+
+    // Upload an object as the tenant.
+    let object = Object {
+        data: "strawberry".into(),
+    };
+    let id = object.create(&tenant).await.unwrap();
+
+    // The object is stored under the tenant's prefix.
+    assert_eq!(Some(object), Object::read(&tenant, &id).await.unwrap());
+    assert_eq!(None, Object::read(&tenant.inner, &id).await.unwrap());
+}
+
+#[tokio::test]
+async fn tenant_isolation() {
+    // The keys are checked before anything is sent, so no server is needed.
+    let base_url: minio::s3::http::BaseUrl = ADDR.parse().unwrap();
+    let client = minio::s3::Client::new(base_url, None, None, None).unwrap();
+    let tenant = |tenant: &str| {
+        TenantScoped::new(
+            S3Backing::new(client.clone(), BUCKET.into()),
+            tenant.to_string(),
+        )
+    };
+
+    // Tenant `a` can't reach id `c` of tenant `a/b` through the id `b/c`.
+    let a = tenant("a");
+    let id = storage_noodle_traits::AssocId::new("b/c".to_string());
+    assert!(matches!(
+        Object::read(&a, &id).await,
+        Err(minio::s3::error::Error::InvalidObjectName(_))
+    ));
+    assert!(Object::delete(&a, &id).await.is_err());
+    let object = Object {
+        data: "lemon".into(),
+    };
+    assert!(object.update(&a, &id).await.is_err());
+
+    // Tenant ids can't contain `/` either.
+    let a_b = tenant("a/b");
+    let id = storage_noodle_traits::AssocId::new("c".to_string());
+    assert!(Object::read(&a_b, &id).await.is_err());
+    assert!(object.create(&a_b).await.is_err());
+}
+
+/// Copies every object to [`OTHER_BUCKET`], and checks the copy of the object with the id.
+async fn copy_to_other_bucket(
+    backing: &S3Backing,
+    id: &storage_noodle_traits::AssocId<Object, String>,
+) {
+    let other = S3Backing::new(backing.client.clone(), OTHER_BUCKET.into());
+    let ids = copy_all::<Object, _, _>(backing, &other).await.unwrap();
+    assert_eq!(ids.len(), Object::list(backing).await.unwrap().len());
+    assert_eq!(
+        Object::read(backing, id).await.unwrap(),
+        Object::read(&other, &ids.get(id).unwrap()).await.unwrap()
+    );
+}
+
+/// Dumps every object to an archive, restores them to [`OTHER_BUCKET`], and checks the restored
+/// object with the id.
+async fn dump_to_other_bucket(
+    backing: &S3Backing,
+    id: &storage_noodle_traits::AssocId<Object, String>,
+) {
+    let path = std::env::temp_dir().join(format!("s3-dump-{}", std::process::id()));
+    let mut archive = Archive::create(&path).unwrap();
+    dump::<Object, _>(&mut archive, "objects", backing)
+        .await
+        .unwrap();
+    assert!(path.join("objects").is_dir());
+
+    let other = S3Backing::new(backing.client.clone(), OTHER_BUCKET.into());
+    let ids = restore::<Object, _>(&archive, "objects", &other)
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(&path).unwrap();
+    assert_eq!(
+        Object::read(backing, id).await.unwrap(),
+        Object::read(&other, &ids.get(id).unwrap()).await.unwrap()
+    );
+}
+
+/// Creates, reads, and deletes an object through the blocking API.
+fn blocking_round_trip(client: minio::s3::Client) {
+    let blocking = Blocking::new(S3Backing::new(client, BUCKET.into())).unwrap();
+    let object = Object {
+        data: "blocking".into(),
+    };
+    let id = blocking::Create::create(&object, &blocking).unwrap();
+    assert_eq!(Some(object), blocking::Read::read(&blocking, &id).unwrap());
+    <Object as blocking::Delete<_>>::delete(&blocking, &id)
+        .unwrap()
+        .unwrap();
 }
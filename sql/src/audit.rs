@@ -0,0 +1,145 @@
+use storage_noodle_traits::audit::{AuditRecord, AuditSink};
+
+use crate::schema::{ColumnType, IdGeneration, MakeSqlTable, SqlColumn, SqlTable};
+
+/// The name of the audit log table.
+pub const AUDIT_TABLE_NAME: &str = "AuditLog";
+
+/// The audit log table, add it to a schema with [`crate::schema::SchemaBuilder::add_type`].
+///
+/// Timestamps are stored as milliseconds since the unix epoch, ids as text, and payloads as JSON
+/// text. The column names aren't reserved words in any of the supported databases, so the queries
+/// don't need to quote them.
+#[derive(Debug)]
+pub struct AuditLog;
+
+impl<DB: sqlx::Database> MakeSqlTable<DB> for AuditLog
+where
+    i64: sqlx::Type<DB>,
+    String: sqlx::Type<DB>,
+{
+    fn table() -> SqlTable {
+        /// Creates a column description.
+        fn column<DB: sqlx::Database, T: sqlx::Type<DB>>(
+            name: &str,
+            column_type: ColumnType,
+            nullable: bool,
+        ) -> SqlColumn {
+            SqlColumn {
+                name: name.to_string(),
+                ty: sqlx::TypeInfo::name(&T::type_info()).to_string(),
+                column_type,
+                references: None,
+                default: None,
+                nullable,
+                check: None,
+            }
+        }
+
+        SqlTable {
+            name: AUDIT_TABLE_NAME.to_string(),
+            schema: None,
+            columns: vec![
+                column::<DB, i64>(
+                    "Id",
+                    ColumnType::PrimaryKey(IdGeneration::AutoIncrement),
+                    false,
+                ),
+                column::<DB, String>("Actor", ColumnType::Data, false),
+                column::<DB, i64>("Timestamp", ColumnType::Data, false),
+                column::<DB, String>("TypeName", ColumnType::Data, false),
+                column::<DB, String>("ItemId", ColumnType::Data, false),
+                column::<DB, String>("Operation", ColumnType::Data, false),
+                column::<DB, String>("BeforeJson", ColumnType::Data, true),
+                column::<DB, String>("AfterJson", ColumnType::Data, true),
+            ],
+            indexes: Vec::new(),
+            checks: Vec::new(),
+        }
+    }
+}
+
+/// Adds a value to `arguments`, and appends it's placeholder to `query`.
+fn bind<'q, DB: sqlx::Database>(
+    arguments: &mut DB::Arguments<'q>,
+    query: &mut String,
+    value: impl sqlx::Encode<'q, DB> + sqlx::Type<DB> + 'q,
+) -> Result<(), sqlx::Error> {
+    if sqlx::Arguments::len(arguments) > 0 {
+        query.push_str(", ");
+    }
+
+    sqlx::Arguments::add(arguments, value).map_err(sqlx::Error::Encode)?;
+    sqlx::Arguments::format_placeholder(arguments, query)
+        .map_err(|e| sqlx::Error::Protocol(e.to_string()))
+}
+
+/// An [`AuditSink`] that inserts records into the [`AuditLog`] table.
+#[derive(Debug, Clone)]
+pub struct SqlAuditSink<DB: sqlx::Database> {
+    /// The pool that the audit log table lives in.
+    pub pool: sqlx::Pool<DB>,
+}
+
+impl<DB: sqlx::Database> SqlAuditSink<DB> {
+    /// Create a new instance.
+    #[must_use]
+    pub const fn new(pool: sqlx::Pool<DB>) -> Self {
+        Self { pool }
+    }
+}
+
+impl<DB, RawId> AuditSink<RawId> for SqlAuditSink<DB>
+where
+    DB: sqlx::Database,
+    for<'c> &'c mut DB::Connection: sqlx::Executor<'c, Database = DB>,
+    for<'q> DB::Arguments<'q>: sqlx::IntoArguments<'q, DB>,
+    for<'q> i64: sqlx::Encode<'q, DB> + sqlx::Type<DB>,
+    for<'q> String: sqlx::Encode<'q, DB> + sqlx::Type<DB>,
+    for<'q> Option<String>: sqlx::Encode<'q, DB> + sqlx::Type<DB>,
+    RawId: core::fmt::Display + Sync,
+{
+    type Error = sqlx::Error;
+
+    async fn record(&self, record: &AuditRecord<RawId>) -> Result<(), Self::Error> {
+        let timestamp = record
+            .timestamp
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |since| {
+                i64::try_from(since.as_millis()).unwrap_or(i64::MAX)
+            });
+
+        // Declared before `arguments` so that it outlives them.
+        let mut query = format!(
+            "INSERT INTO {AUDIT_TABLE_NAME} (Actor, Timestamp, TypeName, ItemId, Operation, BeforeJson, AfterJson) VALUES ("
+        );
+
+        // Bind the values, writing a placeholder for each one.
+        let mut arguments = DB::Arguments::default();
+        bind(&mut arguments, &mut query, record.actor.clone())?;
+        bind(&mut arguments, &mut query, timestamp)?;
+        bind(&mut arguments, &mut query, record.type_name.clone())?;
+        bind(&mut arguments, &mut query, record.id.to_string())?;
+        bind(
+            &mut arguments,
+            &mut query,
+            record.operation.as_str().to_string(),
+        )?;
+        bind(
+            &mut arguments,
+            &mut query,
+            record.before.as_ref().map(ToString::to_string),
+        )?;
+        bind(
+            &mut arguments,
+            &mut query,
+            record.after.as_ref().map(ToString::to_string),
+        )?;
+        query.push_str(");");
+
+        sqlx::query_with(&query, arguments)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+    }
+}
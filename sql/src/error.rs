@@ -0,0 +1,80 @@
+//! Classification of the errors caused by constraint violations.
+//!
+//! The derived operations return a plain [`sqlx::Error`] (or a `HookError` wrapping one), which
+//! [`ConstraintError`] classifies, rather than an error enum of their own. Every derived trait of a
+//! struct then shares one error type, which the wrapper storages (audit, integrity, and hooks)
+//! require with bounds like `Update<S, Error = <T as Read<S>>::Error>`, and callers that don't care
+//! about constraints can keep using `?` into a `sqlx::Error`.
+//!
+//! Callers that do care import [`ConstraintError`] and call
+//! [`ConstraintError::constraint_violation`] or [`ConstraintError::is_conflict`]. The derived
+//! `CreateWithId` uses the same check to tell an id that already exists apart from other conflicts.
+
+use storage_noodle_traits::hooks::HookError;
+
+/// The kind of constraint that a write violated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintKind {
+    /// A unique index (or the primary key) already has a row with the same values.
+    Unique,
+
+    /// A foreign key references a row that doesn't exist, or a referenced row was deleted.
+    ForeignKey,
+
+    /// A `NOT NULL` column was set to `NULL`.
+    NotNull,
+
+    /// A row didn't meet a `CHECK` constraint.
+    Check,
+}
+
+/// A constraint that a write violated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    /// The kind of constraint.
+    pub kind: ConstraintKind,
+
+    /// The name of the constraint or index, if the database reports it (`SQLite` doesn't).
+    pub constraint: Option<String>,
+}
+
+/// Errors that can be caused by a constraint violation, such as the errors returned by the
+/// derived `Create` and `Update` implementations.
+pub trait ConstraintError {
+    /// Returns the violated constraint, or [`None`] if the error wasn't caused by one.
+    fn constraint_violation(&self) -> Option<ConstraintViolation>;
+
+    /// Whether the error is a conflict: the write would have given a unique index (or the primary
+    /// key) a duplicate value.
+    fn is_conflict(&self) -> bool {
+        self.constraint_violation()
+            .is_some_and(|violation| violation.kind == ConstraintKind::Unique)
+    }
+}
+
+impl ConstraintError for sqlx::Error {
+    fn constraint_violation(&self) -> Option<ConstraintViolation> {
+        let error = self.as_database_error()?;
+        let kind = match error.kind() {
+            sqlx::error::ErrorKind::UniqueViolation => ConstraintKind::Unique,
+            sqlx::error::ErrorKind::ForeignKeyViolation => ConstraintKind::ForeignKey,
+            sqlx::error::ErrorKind::NotNullViolation => ConstraintKind::NotNull,
+            sqlx::error::ErrorKind::CheckViolation => ConstraintKind::Check,
+            _ => return None,
+        };
+
+        Some(ConstraintViolation {
+            kind,
+            constraint: error.constraint().map(ToString::to_string),
+        })
+    }
+}
+
+impl<E: ConstraintError, ValidationError> ConstraintError for HookError<E, ValidationError> {
+    fn constraint_violation(&self) -> Option<ConstraintViolation> {
+        match self {
+            Self::Validation(_) => None,
+            Self::Storage(error) => error.constraint_violation(),
+        }
+    }
+}
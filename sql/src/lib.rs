@@ -8,8 +8,14 @@ use storage_noodle_traits::BackingStorage;
 /// SQL schema generation functionality.
 pub mod schema;
 
+pub mod error;
+
+/// SQL audit log sink.
+#[cfg(feature = "audit")]
+pub mod audit;
+
 /// A SQL [`BackingStorage`] implementation.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct SqlBacking<DB: sqlx::Database, RawId> {
     /// The internal [`sqlx`] database pool.
     pub pool: sqlx::Pool<DB>,
@@ -29,6 +35,13 @@ impl<DB: sqlx::Database, RawId> SqlBacking<DB, RawId> {
     }
 }
 
+impl<DB: sqlx::Database, RawId> Clone for SqlBacking<DB, RawId> {
+    /// Clones the pool handle, sharing the connections.
+    fn clone(&self) -> Self {
+        Self::new(self.pool.clone())
+    }
+}
+
 impl<DB: sqlx::Database, RawId> BackingStorage for SqlBacking<DB, RawId> {
     type RawId = RawId;
 }
@@ -36,4 +49,20 @@ impl<DB: sqlx::Database, RawId> BackingStorage for SqlBacking<DB, RawId> {
 #[doc(hidden)]
 pub mod macro_helpers {
     pub use storage_noodle_traits::*;
+
+    /// Returns the current unix time in seconds.
+    #[must_use]
+    pub fn unix_now() -> i64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |since| {
+                i64::try_from(since.as_secs()).unwrap_or(i64::MAX)
+            })
+    }
+
+    /// Returns the unix time (in seconds) that an item created now with a time-to-live expires at.
+    #[must_use]
+    pub fn expires_at(ttl: core::time::Duration) -> i64 {
+        unix_now().saturating_add(i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX))
+    }
 }
@@ -6,14 +6,39 @@ pub mod sqlite;
 #[cfg(feature = "postgres_schema")]
 pub mod postgres;
 
+/// Mysql schema generator.
+#[cfg(feature = "mysql_schema")]
+pub mod mysql;
+
 /// Represents an SQL table.
 #[derive(Debug)]
 pub struct SqlTable {
     /// The table's name.
     pub name: String,
 
+    /// The schema that the table is in (the default one if [`None`]).
+    pub schema: Option<String>,
+
     /// The table's columns.
     pub columns: Vec<SqlColumn>,
+
+    /// The table's indexes.
+    pub indexes: Vec<SqlIndex>,
+
+    /// The SQL conditions that every row must meet (`CHECK` constraints on several columns).
+    pub checks: Vec<String>,
+}
+
+impl SqlTable {
+    /// Returns the names of the primary key columns (in order).
+    #[must_use]
+    pub fn primary_key(&self) -> Vec<&str> {
+        self.columns
+            .iter()
+            .filter(|column| matches!(column.column_type, ColumnType::PrimaryKey(_)))
+            .map(|column| column.name.as_str())
+            .collect()
+    }
 }
 
 /// Represents an SQL column.
@@ -27,6 +52,136 @@ pub struct SqlColumn {
 
     /// The column's `ColumnType`.
     pub column_type: ColumnType,
+
+    /// The foreign key that the column references (if any).
+    pub references: Option<ForeignKey>,
+
+    /// The SQL expression that the column defaults to (if any).
+    pub default: Option<String>,
+
+    /// Whether the column can be `NULL`.
+    pub nullable: bool,
+
+    /// The SQL condition that the column's values must meet (if any).
+    pub check: Option<String>,
+}
+
+impl SqlColumn {
+    /// Returns `true` if the column is declared `PRIMARY KEY` itself, which is the case for the
+    /// primary key unless it is `composite` (declared with a table constraint instead).
+    #[must_use]
+    pub fn inline_primary_key(&self, composite: bool) -> bool {
+        matches!(self.column_type, ColumnType::PrimaryKey(_)) && !composite
+    }
+
+    /// Returns the column's `NOT NULL`, `DEFAULT`, and `CHECK` constraints, each preceded by a
+    /// space, which are written the same way in every dialect. `NOT NULL` is left out if the
+    /// dialect already makes the column's definition `NOT NULL` (`implicit_not_null`), such as a
+    /// `PRIMARY KEY` in `Postgres` and `MySql`. `SQLite` only does so for `INTEGER PRIMARY KEY`
+    /// columns, and allows `NULL` in other primary keys.
+    #[must_use]
+    pub fn constraints(&self, implicit_not_null: bool) -> String {
+        let mut constraints = String::new();
+        if !self.nullable && !implicit_not_null {
+            constraints.push_str(" NOT NULL");
+        }
+        if let Some(default) = &self.default {
+            constraints.push_str(" DEFAULT ");
+            constraints.push_str(default);
+        }
+        if let Some(check) = &self.check {
+            constraints.push_str(" CHECK (");
+            constraints.push_str(check);
+            constraints.push(')');
+        }
+        constraints
+    }
+}
+
+/// Represents an index on some of a table's columns.
+#[derive(Debug)]
+pub struct SqlIndex {
+    /// The index's name.
+    pub name: String,
+
+    /// The names of the indexed columns (in order).
+    pub columns: Vec<String>,
+
+    /// Whether the index is unique (no two rows can have the same values in its columns).
+    pub unique: bool,
+}
+
+/// Represents a foreign key constraint on a column.
+#[derive(Debug)]
+pub struct ForeignKey {
+    /// The schema that the referenced table is in (the default one if [`None`]).
+    pub schema: Option<String>,
+
+    /// The referenced table's name.
+    pub table: String,
+
+    /// The referenced column's name.
+    pub column: String,
+
+    /// What happens to the row when the referenced row is deleted.
+    pub on_delete: OnDelete,
+}
+
+impl ForeignKey {
+    /// Generate the `REFERENCES` constraint.
+    #[must_use]
+    pub fn to_sql(&self) -> String {
+        let Self {
+            schema,
+            table,
+            column,
+            on_delete,
+        } = self;
+
+        let table = qualified_name(schema.as_deref(), table);
+        match on_delete.to_sql() {
+            Some(action) => format!("REFERENCES {table}({column}) ON DELETE {action}"),
+            None => format!("REFERENCES {table}({column})"),
+        }
+    }
+}
+
+/// Qualifies a table name with its schema (if any).
+fn qualified_name(schema: Option<&str>, name: &str) -> String {
+    match schema {
+        Some(schema) => format!("{schema}.{name}"),
+        None => name.to_string(),
+    }
+}
+
+/// Represents the action taken when a referenced row is deleted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnDelete {
+    /// Use the database's default (`NO ACTION`), which rejects the delete.
+    #[default]
+    NoAction,
+
+    /// Reject the delete.
+    Restrict,
+
+    /// Delete the referencing row too.
+    Cascade,
+
+    /// Set the referencing column to `NULL`.
+    SetNull,
+}
+
+impl OnDelete {
+    /// Returns the SQL action, or [`None`] for the database's default.
+    #[must_use]
+    pub const fn to_sql(self) -> Option<&'static str> {
+        match self {
+            Self::NoAction => None,
+            Self::Restrict => Some("RESTRICT"),
+            Self::Cascade => Some("CASCADE"),
+            Self::SetNull => Some("SET NULL"),
+        }
+    }
 }
 
 /// Represents what kind of column a column is.
@@ -35,14 +190,53 @@ pub enum ColumnType {
     /// A data column.
     Data,
 
-    /// A primary key column.
-    PrimaryKey,
+    /// A primary key column, and how its values are generated.
+    PrimaryKey(IdGeneration),
+}
+
+/// Represents how the values of a primary key column are generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdGeneration {
+    /// The database assigns increasing integers (an auto-increment or identity column). Ids can
+    /// still be supplied explicitly (e.g. by `CreateWithId`). `MySql` and `SQLite` continue after
+    /// the largest id, while `Postgres` identity columns (`GENERATED BY DEFAULT`) have a sequence
+    /// that the derived `CreateWithId` moves past the ids it inserts. Ids inserted some other way
+    /// need `setval` to be called on the sequence.
+    AutoIncrement,
+
+    /// The database fills in the column's `default` (e.g. `gen_random_uuid()`).
+    Default,
+
+    /// The client always supplies the id (e.g. an id generator, or a natural key).
+    Client,
 }
 
 /// Trait for generating SQL schemas for a type.
 pub trait MakeSqlTable<DB: sqlx::Database> {
     /// Returns the SQL table for the type.
     fn table() -> SqlTable;
+
+    /// Returns the name of the SQL table for the type.
+    #[must_use]
+    fn table_name() -> String {
+        Self::table().name
+    }
+
+    /// Returns the schema that the SQL table for the type is in (the default one if [`None`]).
+    #[must_use]
+    fn table_schema() -> Option<String> {
+        Self::table().schema
+    }
+
+    /// Returns the name of the primary key column of the SQL table for the type.
+    #[must_use]
+    fn id_column_name() -> String {
+        Self::table()
+            .columns
+            .into_iter()
+            .find(|column| matches!(column.column_type, ColumnType::PrimaryKey(_)))
+            .map_or_else(|| "Id".to_string(), |column| column.name)
+    }
 }
 
 /// Builder API for constructing full SQL schemas.
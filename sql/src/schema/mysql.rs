@@ -0,0 +1,158 @@
+/// Table options for the generated `CREATE TABLE` queries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableOptions {
+    /// The storage engine (`InnoDB` by default, which is needed for foreign keys).
+    pub engine: String,
+
+    /// The default character set (`utf8mb4` by default).
+    pub charset: String,
+
+    /// The default collation (the character set's default if [`None`]).
+    pub collation: Option<String>,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self {
+            engine: "InnoDB".to_string(),
+            charset: "utf8mb4".to_string(),
+            collation: None,
+        }
+    }
+}
+
+impl TableOptions {
+    /// Generate a CREATE TABLE schema query for mysql, with these options.
+    #[must_use]
+    pub fn generate_schema(&self, table: &super::SqlTable) -> String {
+        // Composite primary keys are a table constraint, rather than a column constraint.
+        let primary_key = table.primary_key();
+        let composite = primary_key.len() > 1;
+        let mut columns = table
+            .columns
+            .iter()
+            .map(|column| {
+                let indexed = table
+                    .indexes
+                    .iter()
+                    .any(|index| index.columns.contains(&column.name));
+                generate_row(column, composite, indexed)
+            })
+            .collect::<Vec<_>>();
+        if composite {
+            let primary_key = primary_key.into_iter().map(quote).collect::<Vec<_>>();
+            columns.push(format!("PRIMARY KEY ({})", primary_key.join(", ")));
+        }
+        columns.extend(table.checks.iter().map(|check| format!("CHECK ({check})")));
+
+        // InnoDB ignores inline `REFERENCES`, so foreign keys are table constraints too.
+        for column in &table.columns {
+            if let Some(foreign_key) = &column.references {
+                columns.push(generate_foreign_key(&column.name, foreign_key));
+            }
+        }
+
+        // Indexes are part of the table definition.
+        for index in &table.indexes {
+            let indexed = index.columns.iter().map(|c| quote(c)).collect::<Vec<_>>();
+            columns.push(format!(
+                "{}INDEX {} ({})",
+                if index.unique { "UNIQUE " } else { "" },
+                quote(&index.name),
+                indexed.join(", ")
+            ));
+        }
+
+        let mut options = format!("ENGINE={} DEFAULT CHARSET={}", self.engine, self.charset);
+        if let Some(collation) = &self.collation {
+            options = format!("{options} COLLATE={collation}");
+        }
+
+        format!(
+            "CREATE TABLE {} ({}) {options};",
+            qualified_name(table.schema.as_deref(), &table.name),
+            columns.join(", ")
+        )
+    }
+}
+
+/// Generate a CREATE TABLE schema query for mysql, with the default [`TableOptions`].
+#[must_use]
+pub fn generate_schema(table: &super::SqlTable) -> String {
+    TableOptions::default().generate_schema(table)
+}
+
+/// Generate the table rows, types, and constraints for mysql.
+fn generate_row(sql_column: &super::SqlColumn, composite: bool, indexed: bool) -> String {
+    let super::SqlColumn {
+        name,
+        ty,
+        column_type,
+        references,
+        ..
+    } = sql_column;
+
+    // Keys and indexed columns can't be `TEXT` or `BLOB`, so they get a bounded type instead.
+    let is_key =
+        matches!(column_type, super::ColumnType::PrimaryKey(_)) || references.is_some() || indexed;
+    let ty = process_type(ty, is_key);
+
+    // Only integer columns can be `AUTO_INCREMENT`.
+    let column = match column_type {
+        super::ColumnType::PrimaryKey(super::IdGeneration::AutoIncrement)
+            if !composite && ty.contains("INT") =>
+        {
+            format!("{} {ty} PRIMARY KEY AUTO_INCREMENT", quote(name))
+        }
+        super::ColumnType::PrimaryKey(_) if !composite => {
+            format!("{} {ty} PRIMARY KEY", quote(name))
+        }
+        super::ColumnType::Data | super::ColumnType::PrimaryKey(_) => {
+            format!("{} {ty}", quote(name))
+        }
+    };
+
+    format!(
+        "{column}{}",
+        sql_column.constraints(sql_column.inline_primary_key(composite))
+    )
+}
+
+/// Generate the `FOREIGN KEY` table constraint for a column.
+fn generate_foreign_key(column: &str, foreign_key: &super::ForeignKey) -> String {
+    let constraint = format!(
+        "FOREIGN KEY ({}) REFERENCES {}({})",
+        quote(column),
+        qualified_name(foreign_key.schema.as_deref(), &foreign_key.table),
+        quote(&foreign_key.column)
+    );
+    match foreign_key.on_delete.to_sql() {
+        Some(action) => format!("{constraint} ON DELETE {action}"),
+        None => constraint,
+    }
+}
+
+/// Process types. `sqlx` names strings and byte strings `VARCHAR` and `VARBINARY`, which need a
+/// length.
+fn process_type(ty: &str, is_key: bool) -> String {
+    match (ty, is_key) {
+        ("VARCHAR" | "CHAR" | "TEXT", true) => "VARCHAR(255)".to_string(),
+        ("VARBINARY" | "BINARY" | "BLOB", true) => "VARBINARY(255)".to_string(),
+        ("VARCHAR" | "CHAR", false) => "TEXT".to_string(),
+        ("VARBINARY" | "BINARY", false) => "BLOB".to_string(),
+        _ => ty.to_string(),
+    }
+}
+
+/// Quotes a table name with backticks, qualifying it with its schema (if any).
+fn qualified_name(schema: Option<&str>, name: &str) -> String {
+    match schema {
+        Some(schema) => format!("{}.{}", quote(schema), quote(name)),
+        None => quote(name),
+    }
+}
+
+/// Quotes an identifier with backticks.
+fn quote(name: &str) -> String {
+    format!("`{name}`")
+}
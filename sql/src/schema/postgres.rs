@@ -1,23 +1,74 @@
-/// Generate a CREATE TABLE schema query for postgres.
+/// Generate a CREATE TABLE schema query for postgres, followed by a CREATE INDEX query for each
+/// index.
+#[must_use]
 pub fn generate_schema(table: &super::SqlTable) -> String {
-    let columns = table.columns.iter().map(generate_row).collect::<Vec<_>>();
+    // Composite primary keys are a table constraint, rather than a column constraint.
+    let primary_key = table.primary_key();
+    let composite = primary_key.len() > 1;
+    let mut columns = table
+        .columns
+        .iter()
+        .map(|column| generate_row(column, composite))
+        .collect::<Vec<_>>();
+    if composite {
+        columns.push(format!("PRIMARY KEY ({})", primary_key.join(", ")));
+    }
+    columns.extend(table.checks.iter().map(|check| format!("CHECK ({check})")));
+
+    // Indexes are created in the table's schema.
+    let indexes = table
+        .indexes
+        .iter()
+        .map(|index| {
+            format!(
+                "CREATE {}INDEX IF NOT EXISTS {} ON {} ({});",
+                if index.unique { "UNIQUE " } else { "" },
+                index.name,
+                super::qualified_name(table.schema.as_deref(), &table.name),
+                index.columns.join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .concat();
 
-    format!("CREATE TABLE {} ({});", table.name, columns.join(", "))
+    format!(
+        "CREATE TABLE {} ({});{indexes}",
+        super::qualified_name(table.schema.as_deref(), &table.name),
+        columns.join(", ")
+    )
 }
 
 /// Generate the table rows, types, and constraints for postgres.
-fn generate_row(sql_column: &super::SqlColumn) -> String {
+fn generate_row(sql_column: &super::SqlColumn, composite: bool) -> String {
     let super::SqlColumn {
         name,
         ty,
         column_type,
+        references,
+        ..
     } = sql_column;
 
     let ty = process_type(ty.clone());
 
-    match column_type {
-        super::ColumnType::Data => format!("{name} {ty}"),
-        super::ColumnType::PrimaryKey => format!("{name} {ty} PRIMARY KEY"),
+    // Only integer columns can be identity columns. They are `BY DEFAULT`, so that ids can still be
+    // supplied explicitly.
+    let column = match column_type {
+        super::ColumnType::PrimaryKey(super::IdGeneration::AutoIncrement)
+            if !composite && matches!(ty.as_str(), "INT2" | "INT4" | "INT8") =>
+        {
+            format!("{name} {ty} GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY")
+        }
+        super::ColumnType::PrimaryKey(_) if !composite => format!("{name} {ty} PRIMARY KEY"),
+        super::ColumnType::Data | super::ColumnType::PrimaryKey(_) => format!("{name} {ty}"),
+    };
+    let column = format!(
+        "{column}{}",
+        sql_column.constraints(sql_column.inline_primary_key(composite))
+    );
+
+    match references {
+        Some(foreign_key) => format!("{column} {}", foreign_key.to_sql()),
+        None => column,
     }
 }
 
@@ -1,19 +1,77 @@
-/// Generate a CREATE TABLE schema query for sqlite.
+/// Generate a CREATE TABLE schema query for sqlite, followed by a CREATE INDEX query for each
+/// index.
+#[must_use]
 pub fn generate_schema(table: &super::SqlTable) -> String {
-    let columns = table.columns.iter().map(generate_row).collect::<Vec<_>>();
+    // Composite primary keys are a table constraint, rather than a column constraint.
+    let primary_key = table.primary_key();
+    let composite = primary_key.len() > 1;
+    let mut columns = table
+        .columns
+        .iter()
+        .map(|column| generate_row(column, composite))
+        .collect::<Vec<_>>();
+    if composite {
+        columns.push(format!("PRIMARY KEY ({})", primary_key.join(", ")));
+    }
+    columns.extend(table.checks.iter().map(|check| format!("CHECK ({check})")));
+
+    // Sqlite qualifies the index name (rather than the table name) with the schema.
+    let indexes = table
+        .indexes
+        .iter()
+        .map(|index| {
+            format!(
+                "CREATE {}INDEX IF NOT EXISTS {} ON {} ({});",
+                if index.unique { "UNIQUE " } else { "" },
+                super::qualified_name(table.schema.as_deref(), &index.name),
+                table.name,
+                index.columns.join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .concat();
 
-    format!("CREATE TABLE {} ({});", table.name, columns.join(", "))
+    format!(
+        "CREATE TABLE {} ({});{indexes}",
+        super::qualified_name(table.schema.as_deref(), &table.name),
+        columns.join(", ")
+    )
 }
 
 /// Generate the table rows, types, and constraints for sqlite.
-fn generate_row(sql_column: &super::SqlColumn) -> String {
+fn generate_row(sql_column: &super::SqlColumn, composite: bool) -> String {
     let super::SqlColumn {
         name,
         ty,
         column_type,
+        references,
+        ..
     } = sql_column;
-    match column_type {
-        super::ColumnType::Data => format!("{name} {ty}"),
-        super::ColumnType::PrimaryKey => format!("{name} {ty} PRIMARY KEY"),
+    // Only `INTEGER PRIMARY KEY` columns (aliases of the rowid) can be `AUTOINCREMENT`.
+    let column = match column_type {
+        super::ColumnType::PrimaryKey(super::IdGeneration::AutoIncrement)
+            if !composite && ty == "INTEGER" =>
+        {
+            format!("{name} {ty} PRIMARY KEY AUTOINCREMENT")
+        }
+        super::ColumnType::PrimaryKey(_) if !composite => format!("{name} {ty} PRIMARY KEY"),
+        super::ColumnType::Data | super::ColumnType::PrimaryKey(_) => format!("{name} {ty}"),
+    };
+    // Only `INTEGER PRIMARY KEY` columns are implicitly `NOT NULL`.
+    let implicit_not_null = sql_column.inline_primary_key(composite) && ty == "INTEGER";
+    let column = format!("{column}{}", sql_column.constraints(implicit_not_null));
+
+    // Sqlite only references tables in the same schema, which can't be qualified.
+    match references {
+        Some(foreign_key) => {
+            let foreign_key = super::ForeignKey {
+                schema: None,
+                table: foreign_key.table.clone(),
+                column: foreign_key.column.clone(),
+                on_delete: foreign_key.on_delete,
+            };
+            format!("{column} {}", foreign_key.to_sql())
+        }
+        None => column,
     }
 }
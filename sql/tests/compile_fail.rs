@@ -0,0 +1,6 @@
+//! Checks that the derives reject invalid options at compile time.
+
+#[test]
+fn compile_fail() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -1,6 +1,27 @@
 //! Integration test for SQL backing storage.
 
-use storage_noodle_traits::{Create, Delete, Read, Update};
+use storage_noodle_sql::error::{ConstraintError, ConstraintKind};
+use storage_noodle_traits::{
+    AssocId, Create, CreateWithId, CreateWithTtl, Delete, List, Purge, PurgeExpired, Read, Restore,
+    Storable, Update,
+    audit::{AuditOperation, AuditRecord, AuditedBacking, JsonLinesSink},
+    blocking::{self, Blocking},
+    dump::{Archive, ArchiveError, BackupError, dump, restore, restore_with_ids},
+    ext::StorageExt,
+    hooks::{HookError, HookedBacking, Hooks},
+    id::{IdGenerator, Snowflake},
+    integrity::{
+        Dangling, IntegrityBacking, IntegrityError, Referenced, References, cascade, find_dangling,
+        restrict,
+    },
+    migrate::{
+        IdMap, Journal, MigrateError, Progress, ReferencesMut, copy_all, copy_all_with,
+        copy_dependents,
+    },
+    reference::Ref,
+    repository::Repository,
+    tenant::TenantScoped,
+};
 
 /// The id type used for referencing items.
 type RawId = u32;
@@ -84,6 +105,1686 @@ async fn main() {
     assert!(should_be_none.is_none());
 }
 
+#[tokio::test]
+async fn missing_ids() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Recipe>()
+    .build();
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+    let backing: storage_noodle_sql::SqlBacking<_, u32> =
+        storage_noodle_sql::SqlBacking::new(db_pool);
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    let recipe = Recipe {
+        ingredients: "eggs, flour, sugar".to_string(),
+    };
+    let recipe_id = recipe.create(&backing).await.unwrap();
+    let missing_id = AssocId::new(recipe_id.as_raw() + 1);
+
+    // Updating a missing item returns `None`, and doesn't create it.
+    assert!(
+        recipe
+            .update(&backing, &missing_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(Recipe::read(&backing, &missing_id).await.unwrap().is_none());
+
+    // Deleting a missing item returns `None`.
+    assert!(
+        Recipe::delete(&backing, &missing_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Updating an item with its current values still finds it.
+    recipe.update(&backing, &recipe_id).await.unwrap().unwrap();
+
+    // Deleting an item twice only deletes it once.
+    Recipe::delete(&backing, &recipe_id).await.unwrap().unwrap();
+    assert!(
+        Recipe::delete(&backing, &recipe_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(recipe.update(&backing, &recipe_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn tenant_scoped() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    // Generate and execute the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Note>()
+    .add_type::<Badge>()
+    .build();
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+
+    // Unique indexes are unique within each tenant.
+    assert!(schema.contains(
+        "CREATE UNIQUE INDEX IF NOT EXISTS Badge_TenantId_code_key ON Badge (TenantId, code);"
+    ));
+
+    // Set up a backing storage for each tenant.
+    let alice: TenantScoped<storage_noodle_sql::SqlBacking<_, u32>> = TenantScoped::new(
+        storage_noodle_sql::SqlBacking::new(db_pool.clone()),
+        "alice".to_string(),
+    );
+    let bob: TenantScoped<storage_noodle_sql::SqlBacking<_, u32>> = TenantScoped::new(
+        storage_noodle_sql::SqlBacking::new(db_pool),
+        "bob".to_string(),
+    );
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // Create a note as alice.
+    let note = Note {
+        text: "buy more chocolate chips".to_string(),
+    };
+    let note_id = note.create(&alice).await.unwrap();
+
+    // Alice can read the note.
+    assert_eq!(Note::read(&alice, &note_id).await.unwrap(), Some(note));
+
+    // Bob can't read, update, or delete the note.
+    assert!(Note::read(&bob, &note_id).await.unwrap().is_none());
+    let bobs_note = Note {
+        text: "alice owes me cookies".to_string(),
+    };
+    assert!(bobs_note.update(&bob, &note_id).await.unwrap().is_none());
+    assert!(Note::delete(&bob, &note_id).await.unwrap().is_none());
+
+    // Alice's note is untouched.
+    let returned_note = Note::read(&alice, &note_id).await.unwrap().unwrap();
+    assert_eq!(returned_note.text, "buy more chocolate chips");
+
+    // Both tenants can use the same unique value, but only once each.
+    let badge = Badge {
+        code: "baker".to_string(),
+    };
+    badge.create(&alice).await.unwrap();
+    badge.create(&bob).await.unwrap();
+    assert!(badge.create(&alice).await.unwrap_err().is_conflict());
+
+    // An explicit id only counts as already existing within the same tenant.
+    let alices_badge = AssocId::new(1);
+    let bobs_badge = AssocId::new(2);
+    let ranger = Badge {
+        code: "ranger".to_string(),
+    };
+    assert_eq!(
+        ranger.create_with_id(&alice, &alices_badge).await.unwrap(),
+        None
+    );
+    assert!(
+        ranger
+            .create_with_id(&bob, &alices_badge)
+            .await
+            .unwrap_err()
+            .is_conflict()
+    );
+    assert!(
+        badge
+            .create_with_id(&alice, &bobs_badge)
+            .await
+            .unwrap_err()
+            .is_conflict()
+    );
+}
+
+#[tokio::test]
+async fn soft_delete() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    // Generate and execute the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Biscuit>()
+    .build();
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+
+    // Set up the backing storage.
+    let backing: storage_noodle_sql::SqlBacking<_, u32> =
+        storage_noodle_sql::SqlBacking::new(db_pool);
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // Create a biscuit.
+    let biscuit = Biscuit {
+        flavour: "ginger".to_string(),
+    };
+    let biscuit_id = biscuit.create(&backing).await.unwrap();
+
+    // Items that aren't deleted can't be restored or purged.
+    assert!(
+        Biscuit::restore(&backing, &biscuit_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        Biscuit::purge(&backing, &biscuit_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Delete the biscuit, it can't be read or updated.
+    Biscuit::delete(&backing, &biscuit_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(
+        Biscuit::read(&backing, &biscuit_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        Biscuit {
+            flavour: "oat".to_string(),
+        }
+        .update(&backing, &biscuit_id)
+        .await
+        .unwrap()
+        .is_none()
+    );
+
+    // Restore the biscuit, it is back to how it was.
+    Biscuit::restore(&backing, &biscuit_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        Biscuit::read(&backing, &biscuit_id).await.unwrap(),
+        Some(biscuit)
+    );
+
+    // Delete and purge the biscuit, it is gone for good.
+    Biscuit::delete(&backing, &biscuit_id)
+        .await
+        .unwrap()
+        .unwrap();
+    Biscuit::purge(&backing, &biscuit_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(
+        Biscuit::restore(&backing, &biscuit_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn expiry() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    // Generate and execute the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Session>()
+    .build();
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+
+    // Set up the backing storage.
+    let backing: storage_noodle_sql::SqlBacking<_, u32> =
+        storage_noodle_sql::SqlBacking::new(db_pool);
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // A session without a TTL never expires.
+    let forever = Session {
+        user: "alice".to_string(),
+    };
+    let forever_id = forever.create(&backing).await.unwrap();
+
+    // A session with a long TTL can be read.
+    let long = Session {
+        user: "bob".to_string(),
+    };
+    let long_id = long
+        .create_with_ttl(&backing, core::time::Duration::from_secs(60 * 60))
+        .await
+        .unwrap();
+    assert_eq!(Session::read(&backing, &long_id).await.unwrap(), Some(long));
+
+    // A session with no TTL left is treated as absent.
+    let expired_id = Session {
+        user: "carol".to_string(),
+    }
+    .create_with_ttl(&backing, core::time::Duration::ZERO)
+    .await
+    .unwrap();
+    assert!(
+        Session::read(&backing, &expired_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Only the expired session is purged.
+    assert_eq!(Session::purge_expired(&backing).await.unwrap(), 1);
+    assert_eq!(Session::purge_expired(&backing).await.unwrap(), 0);
+    assert_eq!(
+        Session::read(&backing, &forever_id).await.unwrap(),
+        Some(forever)
+    );
+    assert!(Session::read(&backing, &long_id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn hooks() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    // Generate and execute the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Comment>()
+    .add_type::<Label>()
+    .build();
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+
+    // Set up the backing storage, and a wrapper that runs hooks.
+    let backing: storage_noodle_sql::SqlBacking<_, u32> =
+        storage_noodle_sql::SqlBacking::new(db_pool.clone());
+    let hooked = HookedBacking::new(storage_noodle_sql::SqlBacking::<_, u32>::new(db_pool));
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // Hooks derived with `#[storage_noodle(hooks)]` reject invalid items.
+    let error = Comment {
+        text: String::new(),
+    }
+    .create(&backing)
+    .await
+    .unwrap_err();
+    assert!(matches!(error, HookError::Validation(ref e) if e == "empty comment"));
+    let error = Comment {
+        text: String::new(),
+    }
+    .create_with_ttl(&backing, core::time::Duration::from_secs(60))
+    .await
+    .unwrap_err();
+    assert!(matches!(error, HookError::Validation(_)));
+
+    // Valid items are stored.
+    let comment = Comment {
+        text: "yum".to_string(),
+    };
+    let comment_id = comment.create(&backing).await.unwrap();
+    let error = Comment {
+        text: String::new(),
+    }
+    .update(&backing, &comment_id)
+    .await
+    .unwrap_err();
+    assert!(matches!(error, HookError::Validation(_)));
+    assert_eq!(
+        Comment::read(&backing, &comment_id).await.unwrap(),
+        Some(comment)
+    );
+    Comment::delete(&backing, &comment_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let comment = Comment {
+        text: "crumbly".to_string(),
+    };
+    let comment_id = comment
+        .create_with_ttl(&backing, core::time::Duration::from_secs(60))
+        .await
+        .unwrap();
+    assert_eq!(
+        Comment::read(&backing, &comment_id).await.unwrap(),
+        Some(comment)
+    );
+
+    // The wrapper runs the hooks of any type.
+    let error = Label {
+        name: "way too long".to_string(),
+    }
+    .create(&hooked)
+    .await
+    .unwrap_err();
+    assert!(matches!(error, HookError::Validation(12)));
+
+    let label = Label {
+        name: "sweet".to_string(),
+    };
+    let label_id = label.create(&hooked).await.unwrap();
+    assert_eq!(Label::read(&hooked, &label_id).await.unwrap(), Some(label));
+    assert_eq!(LABELS_CREATED.load(core::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn id_generation() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    // Generate and execute the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Event>()
+    .build();
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+
+    // Set up the backing storage.
+    let backing: storage_noodle_sql::SqlBacking<_, String> =
+        storage_noodle_sql::SqlBacking::new(db_pool);
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // Ids are UUIDv7s generated client-side, so they sort by creation time.
+    let first = Event {
+        name: "baked".to_string(),
+    };
+    let first_id = first.create(&backing).await.unwrap();
+    let second_id = Event {
+        name: "eaten".to_string(),
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+    assert_eq!(first_id.as_raw().len(), 36);
+    assert!(first_id.as_raw() < second_id.as_raw());
+    assert_eq!(Event::read(&backing, &first_id).await.unwrap(), Some(first));
+
+    // Items can be created with an id that is known up front.
+    let id = storage_noodle_traits::AssocId::new("known".to_string());
+    let event = Event {
+        name: "crumbled".to_string(),
+    };
+    event.create_with_id(&backing, &id).await.unwrap().unwrap();
+    assert_eq!(Event::read(&backing, &id).await.unwrap(), Some(event));
+
+    // Existing items aren't overwritten.
+    let result = Event {
+        name: "dunked".to_string(),
+    }
+    .create_with_id(&backing, &id)
+    .await
+    .unwrap();
+    assert!(result.is_none());
+    let returned_event = Event::read(&backing, &id).await.unwrap().unwrap();
+    assert_eq!(returned_event.name, "crumbled");
+
+    // Snowflake ids are unique and increasing, even within a millisecond.
+    let snowflake = Snowflake::new(7);
+    let ids: Vec<u64> = (0..10_000).map(|_| snowflake.generate()).collect();
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[tokio::test]
+async fn references() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    // Generate and execute the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Recipe>()
+    .add_type::<Cookie<RawId>>()
+    .add_type::<Pie<RawId>>()
+    .build();
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+
+    // Set up the backing storage.
+    let backing: storage_noodle_sql::SqlBacking<_, u32> =
+        storage_noodle_sql::SqlBacking::new(db_pool);
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    let recipe_id = Recipe {
+        ingredients: "apples, flour, butter".to_string(),
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+
+    // An `AssocId` field can fetch the item it references.
+    let cookie_id = Cookie {
+        flavour: "apple".to_string(),
+        recipe: recipe_id,
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+    let cookie = Cookie::read(&backing, &cookie_id).await.unwrap().unwrap();
+    let recipe = cookie.recipe.fetch(&backing).await.unwrap().unwrap();
+    assert_eq!(recipe.ingredients, "apples, flour, butter");
+
+    // A `Ref` field is stored as the id, and loaded on first access.
+    let pie_id = Pie {
+        recipe: recipe_id.into(),
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+    let pie = Pie::read(&backing, &pie_id).await.unwrap().unwrap();
+    assert_eq!(pie.recipe.id(), &recipe_id);
+    assert!(pie.recipe.get().is_none());
+
+    let loaded = pie.recipe.load(&backing).await.unwrap();
+    assert_eq!(loaded, Some(&recipe));
+    assert_eq!(pie.recipe.get(), Some(&recipe));
+
+    // References to missing items load as `None`.
+    let dangling: Ref<Recipe, RawId> = storage_noodle_traits::AssocId::new(1000).into();
+    assert!(dangling.load(&backing).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn foreign_keys() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    // Generate and execute the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Recipe>()
+    .add_type::<Cookie<RawId>>()
+    .add_type::<Topping<RawId>>()
+    .add_type::<Tray<RawId>>()
+    .build();
+    assert!(schema.contains("recipe INTEGER NOT NULL REFERENCES Recipe(Id),"));
+    assert!(schema.contains("recipe INTEGER NOT NULL REFERENCES Recipe(Id) ON DELETE CASCADE,"));
+    assert!(schema.contains("recipe INTEGER REFERENCES Recipe(Id) ON DELETE SET NULL,"));
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+
+    // Set up the backing storage.
+    let backing: storage_noodle_sql::SqlBacking<_, u32> =
+        storage_noodle_sql::SqlBacking::new(db_pool);
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    let recipe_id = Recipe {
+        ingredients: "oats, butter, syrup".to_string(),
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+    let cookie_id = Cookie {
+        flavour: "flapjack".to_string(),
+        recipe: recipe_id,
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+    let topping_id = Topping {
+        name: "raisins".to_string(),
+        recipe: recipe_id,
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+    let tray_id = Tray {
+        recipe: Some(recipe_id),
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+
+    // Items can't reference items that don't exist.
+    Cookie {
+        flavour: "ghost".to_string(),
+        recipe: AssocId::new(1000),
+    }
+    .create(&backing)
+    .await
+    .unwrap_err();
+
+    // The recipe can't be deleted while a cookie references it.
+    Recipe::delete(&backing, &recipe_id).await.unwrap_err();
+    assert!(Recipe::read(&backing, &recipe_id).await.unwrap().is_some());
+
+    // Once the cookie is gone, deleting the recipe deletes the topping and clears the tray.
+    Cookie::delete(&backing, &cookie_id).await.unwrap().unwrap();
+    Recipe::delete(&backing, &recipe_id).await.unwrap().unwrap();
+    assert!(
+        Topping::read(&backing, &topping_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(
+        Tray::read(&backing, &tray_id).await.unwrap(),
+        Some(Tray { recipe: None })
+    );
+}
+
+#[tokio::test]
+async fn integrity() {
+    // Set up the backing storage, without foreign keys (like a backend that can't enforce them).
+    let options =
+        <sqlx::sqlite::SqliteConnectOptions as core::str::FromStr>::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+    let db_pool = sqlx::sqlite::SqlitePool::connect_with(options)
+        .await
+        .unwrap();
+
+    // Generate and execute the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Recipe>()
+    .add_type::<Cookie<RawId>>()
+    .add_type::<Pie<RawId>>()
+    .build();
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+
+    // Set up the backing storage.
+    let backing = IntegrityBacking::new(storage_noodle_sql::SqlBacking::<_, RawId>::new(db_pool));
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    let recipe_id = Recipe {
+        ingredients: "plums, flour, butter".to_string(),
+    }
+    .create(&backing.inner)
+    .await
+    .unwrap();
+    let cookie_id = Cookie {
+        flavour: "plum".to_string(),
+        recipe: recipe_id,
+    }
+    .create(&backing.inner)
+    .await
+    .unwrap();
+    let pie_id = Pie {
+        recipe: recipe_id.into(),
+    }
+    .create(&backing.inner)
+    .await
+    .unwrap();
+
+    // Reads and lists are passed through.
+    assert!(Recipe::read(&backing, &recipe_id).await.unwrap().is_some());
+    assert_eq!(Pie::list(&backing).await.unwrap().len(), 1);
+
+    // Cookies restrict deleting their recipe.
+    assert!(matches!(
+        Recipe::delete(&backing, &recipe_id).await,
+        Err(IntegrityError::Restricted { referenced_by: 1 })
+    ));
+    assert!(Recipe::read(&backing, &recipe_id).await.unwrap().is_some());
+
+    // Pies are deleted with their recipe.
+    Cookie::delete(&backing.inner, &cookie_id)
+        .await
+        .unwrap()
+        .unwrap();
+    Recipe::delete(&backing, &recipe_id).await.unwrap().unwrap();
+    assert!(Pie::read(&backing, &pie_id).await.unwrap().is_none());
+
+    // References that bypass the wrapper are found by a scan.
+    let orphan_id = Cookie {
+        flavour: "orphan".to_string(),
+        recipe: recipe_id,
+    }
+    .create(&backing.inner)
+    .await
+    .unwrap();
+    assert_eq!(
+        find_dangling::<Cookie<RawId>, Recipe, _>(&backing.inner)
+            .await
+            .unwrap(),
+        vec![Dangling {
+            item: orphan_id,
+            reference: recipe_id,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn extensions() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    // Generate and execute the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Recipe>()
+    .build();
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+
+    // Set up the backing storage.
+    let backing: storage_noodle_sql::SqlBacking<_, u32> =
+        storage_noodle_sql::SqlBacking::new(db_pool);
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // Every CRUD operation can start from the backing storage.
+    let recipe = Recipe {
+        ingredients: "rye, water, salt".to_string(),
+    };
+    let recipe_id = backing.create(&recipe).await.unwrap();
+    assert_eq!(backing.read(&recipe_id).await.unwrap(), Some(recipe));
+
+    let recipe = Recipe {
+        ingredients: "rye, water, salt, caraway".to_string(),
+    };
+    backing.update(&recipe_id, &recipe).await.unwrap().unwrap();
+    assert_eq!(
+        backing.list::<Recipe>().await.unwrap(),
+        vec![(recipe_id, recipe)]
+    );
+
+    backing.delete(&recipe_id).await.unwrap().unwrap();
+    assert!(backing.read(&recipe_id).await.unwrap().is_none());
+
+    // A repository binds the type, so services only need the repository.
+    let recipes: Repository<Recipe, _> = Repository::new(backing.clone());
+    let recipe = Recipe {
+        ingredients: "spelt, water, salt".to_string(),
+    };
+    let recipe_id = recipes.create(&recipe).await.unwrap();
+    assert_eq!(recipes.read(&recipe_id).await.unwrap(), Some(recipe));
+    assert_eq!(recipes.list().await.unwrap().len(), 1);
+    recipes.delete(&recipe_id).await.unwrap().unwrap();
+    assert!(recipes.list().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn storable() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    // Generate and execute the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Recipe>()
+    .add_type::<Topping<RawId>>()
+    .build();
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+
+    // Set up the backing storage.
+    let backing: SqliteBacking = storage_noodle_sql::SqlBacking::new(db_pool);
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // A `Storable` derive implements every CRUD trait.
+    round_trip(
+        &backing,
+        Recipe {
+            ingredients: "oats, honey".to_string(),
+        },
+        Recipe {
+            ingredients: "oats, honey, raisins".to_string(),
+        },
+    )
+    .await;
+
+    // `only(...)` limits the derive to the listed operations.
+    let recipe_id = Recipe {
+        ingredients: "sugar".to_string(),
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+    let topping = Topping {
+        name: "sprinkles".to_string(),
+        recipe: recipe_id,
+    };
+    let topping_id = topping.create(&backing).await.unwrap();
+    assert_eq!(
+        Topping::read(&backing, &topping_id).await.unwrap(),
+        Some(topping)
+    );
+}
+
+/// Creates, replaces, and deletes an item, through a single `Storable` bound.
+async fn round_trip<T: Storable<SqliteBacking> + PartialEq + core::fmt::Debug>(
+    backing: &SqliteBacking,
+    item: T,
+    replacement: T,
+) where
+    <T as Create<SqliteBacking>>::Error: core::fmt::Debug,
+    <T as Read<SqliteBacking>>::Error: core::fmt::Debug,
+    <T as Update<SqliteBacking>>::Error: core::fmt::Debug,
+    <T as Delete<SqliteBacking>>::Error: core::fmt::Debug,
+{
+    let id = <T as Create<SqliteBacking>>::create(&item, backing)
+        .await
+        .unwrap();
+    assert_eq!(
+        <T as Read<SqliteBacking>>::read(backing, &id)
+            .await
+            .unwrap(),
+        Some(item)
+    );
+
+    <T as Update<SqliteBacking>>::update(&replacement, backing, &id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        <T as Read<SqliteBacking>>::read(backing, &id)
+            .await
+            .unwrap(),
+        Some(replacement)
+    );
+
+    <T as Delete<SqliteBacking>>::delete(backing, &id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(
+        <T as Read<SqliteBacking>>::read(backing, &id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+/// Sets up an in-memory database with the recipe and cookie tables.
+async fn recipe_book() -> SqliteBacking {
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Recipe>()
+    .add_type::<Cookie<RawId>>()
+    .build();
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+
+    storage_noodle_sql::SqlBacking::new(db_pool)
+}
+
+/// Forwards progress to a [`Journal`], then fails once `left` items were copied.
+struct Interrupted {
+    /// The journal that progress is recorded in.
+    journal: Journal,
+
+    /// The number of items to copy before failing.
+    left: usize,
+}
+
+impl Progress<RawId, RawId> for Interrupted {
+    type Error = std::io::Error;
+
+    fn copied(&mut self, from: &RawId, to: &RawId) -> Result<(), Self::Error> {
+        self.journal.copied(from, to)?;
+        if self.left == 0 {
+            return Err(std::io::Error::other("interrupted"));
+        }
+        self.left -= 1;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn dump_and_restore() {
+    let from = recipe_book().await;
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    for ingredients in ["flour, butter", "flour, cocoa"] {
+        Recipe {
+            ingredients: ingredients.to_string(),
+        }
+        .create(&from)
+        .await
+        .unwrap();
+    }
+
+    // Dump the recipes to an archive.
+    let path = std::env::temp_dir().join(format!("dump-{}", std::process::id()));
+    let mut archive = Archive::create(&path).unwrap();
+    assert_eq!(
+        dump::<Recipe, _>(&mut archive, "recipes", &from)
+            .await
+            .unwrap(),
+        2
+    );
+    let archive = Archive::open(&path).unwrap();
+    assert_eq!(archive.sections().collect::<Vec<_>>(), vec![("recipes", 2)]);
+
+    // Restore them under new ids.
+    let to = recipe_book().await;
+    Recipe {
+        ingredients: "water".to_string(),
+    }
+    .create(&to)
+    .await
+    .unwrap();
+    let ids = restore::<Recipe, _>(&archive, "recipes", &to)
+        .await
+        .unwrap();
+    let new_id = ids.get(&AssocId::new(2)).unwrap();
+    assert_eq!(
+        Recipe::read(&to, &new_id).await.unwrap().unwrap(),
+        Recipe {
+            ingredients: "flour, cocoa".to_string(),
+        }
+    );
+
+    // Restore them under their original ids, which must be free.
+    let to = recipe_book().await;
+    restore_with_ids::<Recipe, _>(&archive, "recipes", &to)
+        .await
+        .unwrap();
+    assert_eq!(
+        Recipe::list(&to).await.unwrap(),
+        Recipe::list(&from).await.unwrap()
+    );
+    assert!(matches!(
+        restore_with_ids::<Recipe, _>(&archive, "recipes", &to).await,
+        Err(BackupError::IdTaken)
+    ));
+
+    // Archives that were changed aren't restored.
+    let section = path.join("recipes.ndjson");
+    let contents = std::fs::read_to_string(&section).unwrap();
+    std::fs::write(&section, contents.replace("cocoa", "carob")).unwrap();
+    let result = restore::<Recipe, _>(&archive, "recipes", &recipe_book().await).await;
+    std::fs::remove_dir_all(&path).unwrap();
+    assert!(matches!(
+        result,
+        Err(BackupError::Archive(ArchiveError::ChecksumMismatch(_)))
+    ));
+}
+
+#[tokio::test]
+async fn migrate() {
+    let from = recipe_book().await;
+    let to = recipe_book().await;
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // The destination already holds a recipe, so the copies get different ids.
+    Recipe {
+        ingredients: "water".to_string(),
+    }
+    .create(&to)
+    .await
+    .unwrap();
+
+    let mut recipe_ids = Vec::new();
+    for ingredients in ["flour, butter", "flour, cocoa", "oats, raisins"] {
+        let recipe_id = Recipe {
+            ingredients: ingredients.to_string(),
+        }
+        .create(&from)
+        .await
+        .unwrap();
+        recipe_ids.push(recipe_id);
+    }
+    Cookie {
+        flavour: "chocolate".to_string(),
+        recipe: recipe_ids[1],
+    }
+    .create(&from)
+    .await
+    .unwrap();
+
+    // Copy the recipes, then the cookies, rewriting their references.
+    let recipes = copy_all::<Recipe, _, _>(&from, &to).await.unwrap();
+    assert_eq!(recipes.len(), 3);
+    let mut cookies = IdMap::new();
+    copy_dependents::<Cookie<RawId>, Recipe, _, _, _>(&from, &to, &recipes, &mut cookies, &mut ())
+        .await
+        .unwrap();
+
+    let copied = Cookie::list(&to).await.unwrap();
+    assert_eq!(copied.len(), 1);
+    let (cookie_id, cookie) = &copied[0];
+    assert_eq!(cookies.iter().next(), Some((&1, cookie_id.as_raw())));
+    assert_ne!(cookie.recipe, recipe_ids[1]);
+    assert_eq!(
+        Recipe::read(&to, &cookie.recipe).await.unwrap().unwrap(),
+        Recipe {
+            ingredients: "flour, cocoa".to_string(),
+        }
+    );
+
+    // An interrupted copy is resumed from its journal, without copying items twice.
+    let to = recipe_book().await;
+    let path = std::env::temp_dir().join(format!("migrate-{}.tsv", std::process::id()));
+    let (journal, mut ids) = Journal::open(&path).unwrap();
+    let mut progress = Interrupted { journal, left: 1 };
+    assert!(matches!(
+        copy_all_with(&from, &to, &mut ids, &mut progress, |_: &mut Recipe| Ok(())).await,
+        Err(MigrateError::Progress(_))
+    ));
+
+    // A crash can leave a partly written last line, which is ignored.
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    std::io::Write::write_all(&mut file, b"3\t9").unwrap();
+
+    let (mut journal, mut ids) = Journal::open(&path).unwrap();
+    assert_eq!(ids.len(), 2);
+    let copied = copy_all_with(&from, &to, &mut ids, &mut journal, |_: &mut Recipe| Ok(()))
+        .await
+        .unwrap();
+    assert_eq!(copied, 1);
+    assert_eq!(ids.len(), 3);
+    assert_eq!(Recipe::list(&to).await.unwrap().len(), 3);
+
+    // The partial line was replaced by the complete one.
+    let (_, reopened) = Journal::open::<Recipe, RawId, RawId>(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(reopened.len(), 3);
+    let third = reopened.get(&AssocId::new(3)).unwrap();
+    assert_ne!(third, AssocId::new(9));
+}
+
+#[test]
+fn blocking() {
+    // Set up the backing storage, connecting the pool on the blocking runtime.
+    let backing: Blocking<SqliteBacking> = Blocking::connect(async {
+        sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+            .await
+            .map(storage_noodle_sql::SqlBacking::new)
+    })
+    .unwrap();
+
+    // Generate and execute the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Recipe>()
+    .build();
+    backing
+        .block_on(sqlx::query(&schema).execute(&backing.inner.pool))
+        .unwrap();
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // Every CRUD operation can be called without a runtime.
+    let recipe = Recipe {
+        ingredients: "cocoa, sugar, milk".to_string(),
+    };
+    let recipe_id = blocking::Create::create(&recipe, &backing).unwrap();
+    assert_eq!(
+        blocking::Read::read(&backing, &recipe_id).unwrap(),
+        Some(recipe)
+    );
+
+    let recipe = Recipe {
+        ingredients: "cocoa, sugar, oat milk".to_string(),
+    };
+    <Recipe as blocking::Update<SqliteBacking>>::update(&recipe, &backing, &recipe_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        <Recipe as blocking::List<_>>::list(&backing).unwrap(),
+        vec![(recipe_id, recipe)]
+    );
+
+    <Recipe as blocking::Delete<_>>::delete(&backing, &recipe_id)
+        .unwrap()
+        .unwrap();
+    assert!(
+        <Recipe as blocking::Read<_>>::read(&backing, &recipe_id)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn audited() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    // Generate and execute the schema, including the audit log table.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Recipe>()
+    .add_type::<storage_noodle_sql::audit::AuditLog>()
+    .build();
+    sqlx::query(&schema).execute(&db_pool).await.unwrap();
+
+    // Set up the audited backing storage.
+    let backing = AuditedBacking::new(
+        storage_noodle_sql::SqlBacking::<_, RawId>::new(db_pool.clone()),
+        storage_noodle_sql::audit::SqlAuditSink::new(db_pool.clone()),
+        "alice".to_string(),
+    );
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // Create, update, and delete a recipe.
+    let recipe_id = Recipe {
+        ingredients: "eggs, flour, sugar, butter".to_string(),
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+    Recipe {
+        ingredients: "eggs, flour, sugar, butter, chocolate chips".to_string(),
+    }
+    .update(&backing, &recipe_id)
+    .await
+    .unwrap()
+    .unwrap();
+    Recipe::delete(&backing, &recipe_id).await.unwrap().unwrap();
+
+    // Every change was recorded, in order.
+    let log: Vec<(String, String, Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT Actor, Operation, BeforeJson, AfterJson FROM AuditLog ORDER BY Id")
+            .fetch_all(&db_pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        log,
+        [
+            (
+                "alice".to_string(),
+                "create".to_string(),
+                None,
+                Some(r#"{"ingredients":"eggs, flour, sugar, butter"}"#.to_string())
+            ),
+            (
+                "alice".to_string(),
+                "update".to_string(),
+                Some(r#"{"ingredients":"eggs, flour, sugar, butter"}"#.to_string()),
+                Some(
+                    r#"{"ingredients":"eggs, flour, sugar, butter, chocolate chips"}"#.to_string()
+                )
+            ),
+            (
+                "alice".to_string(),
+                "delete".to_string(),
+                Some(
+                    r#"{"ingredients":"eggs, flour, sugar, butter, chocolate chips"}"#.to_string()
+                ),
+                None
+            ),
+        ]
+    );
+
+    // Records can also be written as JSON lines.
+    let path = std::env::temp_dir().join(format!("audit-{}.jsonl", std::process::id()));
+    let backing = AuditedBacking::new(
+        backing.inner,
+        JsonLinesSink::open(&path).unwrap(),
+        "bob".to_string(),
+    );
+    let recipe_id = Recipe {
+        ingredients: "eggs, flour, sugar, butter, strawberries".to_string(),
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+
+    let line = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let record: AuditRecord<RawId> = serde_json::from_str(&line).unwrap();
+    assert_eq!(record.actor, "bob");
+    assert_eq!(record.operation, AuditOperation::Create);
+    assert_eq!(&record.id, recipe_id.as_raw());
+}
+
+#[tokio::test]
+async fn naming() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    // The generated schema uses the renamed tables and columns.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Bakery>()
+    .add_type::<Loaf<RawId>>()
+    .build();
+    assert_eq!(
+        schema,
+        "CREATE TABLE bakeries (shop_name TEXT NOT NULL, town TEXT NOT NULL, \
+         id INTEGER PRIMARY KEY AUTOINCREMENT);\
+         CREATE TABLE loaves (bakeryId INTEGER NOT NULL REFERENCES bakeries(id), \
+         loafKind TEXT NOT NULL, deletedAt INTEGER, id INTEGER PRIMARY KEY AUTOINCREMENT);"
+    );
+
+    // The derives map onto existing tables, whatever order their columns are in.
+    sqlx::raw_sql(
+        "
+            CREATE TABLE bakeries (id INTEGER PRIMARY KEY, town TEXT, shop_name TEXT);
+            CREATE TABLE loaves (
+                id INTEGER PRIMARY KEY,
+                loafKind TEXT,
+                deletedAt INTEGER,
+                bakeryId INTEGER REFERENCES bakeries(id)
+            );
+        ",
+    )
+    .execute(&db_pool)
+    .await
+    .unwrap();
+    let backing: storage_noodle_sql::SqlBacking<_, RawId> =
+        storage_noodle_sql::SqlBacking::new(db_pool.clone());
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    let bakery = Bakery {
+        shop_name: "The Crusty Cob".to_string(),
+        location: "Leeds".to_string(),
+    };
+    let bakery_id = bakery.create(&backing).await.unwrap();
+    assert_eq!(
+        Bakery::list(&backing).await.unwrap(),
+        vec![(bakery_id, bakery)]
+    );
+
+    let loaf = Loaf {
+        bakery_id,
+        loaf_kind: "sourdough".to_string(),
+    };
+    let loaf_id = loaf.create(&backing).await.unwrap();
+    assert_eq!(Loaf::read(&backing, &loaf_id).await.unwrap(), Some(loaf));
+
+    // The rows are in the existing columns.
+    let row: (String, String) = sqlx::query_as("SELECT shop_name, town FROM bakeries")
+        .fetch_one(&db_pool)
+        .await
+        .unwrap();
+    assert_eq!(row, ("The Crusty Cob".to_string(), "Leeds".to_string()));
+
+    Loaf::delete(&backing, &loaf_id).await.unwrap().unwrap();
+    let deleted_at: Option<i64> = sqlx::query_scalar("SELECT deletedAt FROM loaves")
+        .fetch_one(&db_pool)
+        .await
+        .unwrap();
+    assert!(deleted_at.is_some());
+}
+
+#[tokio::test]
+async fn primary_keys() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Shelf>()
+    .add_type::<Product>()
+    .add_type::<Page>()
+    .build();
+    assert_eq!(
+        schema,
+        "CREATE TABLE Shelf (label TEXT NOT NULL, shelf_id INTEGER PRIMARY KEY AUTOINCREMENT);\
+         CREATE TABLE Product (sku TEXT PRIMARY KEY NOT NULL, name TEXT NOT NULL);\
+         CREATE TABLE Page (site TEXT NOT NULL, slug TEXT NOT NULL, title TEXT NOT NULL, \
+         PRIMARY KEY (site, slug));"
+    );
+    sqlx::raw_sql(&schema).execute(&db_pool).await.unwrap();
+    let shelves: storage_noodle_sql::SqlBacking<_, RawId> =
+        storage_noodle_sql::SqlBacking::new(db_pool.clone());
+    let products: storage_noodle_sql::SqlBacking<_, String> =
+        storage_noodle_sql::SqlBacking::new(db_pool.clone());
+    let pages: storage_noodle_sql::SqlBacking<_, (String, String)> =
+        storage_noodle_sql::SqlBacking::new(db_pool);
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // The id column can be renamed.
+    let shelf = Shelf {
+        label: "top".to_string(),
+    };
+    let shelf_id = shelf.create(&shelves).await.unwrap();
+    assert_eq!(
+        Shelf::list(&shelves).await.unwrap(),
+        vec![(shelf_id, shelf)]
+    );
+
+    // The id of an item with a natural key is its key.
+    let product = Product {
+        sku: "CK-001".to_string(),
+        name: "chocolate chip cookie".to_string(),
+    };
+    let product_id = product.create(&products).await.unwrap();
+    assert_eq!(product_id.as_raw(), "CK-001");
+    assert_eq!(
+        Product::read(&products, &product_id).await.unwrap(),
+        Some(product)
+    );
+
+    // Updates don't change the key.
+    Product {
+        sku: "CK-002".to_string(),
+        name: "double chocolate chip cookie".to_string(),
+    }
+    .update(&products, &product_id)
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        Product::read(&products, &product_id).await.unwrap(),
+        Some(Product {
+            sku: "CK-001".to_string(),
+            name: "double chocolate chip cookie".to_string(),
+        })
+    );
+
+    // Items created with a known id take their key from it.
+    let other_id = AssocId::new("CK-003".to_string());
+    Product {
+        sku: String::new(),
+        name: "oat cookie".to_string(),
+    }
+    .create_with_id(&products, &other_id)
+    .await
+    .unwrap()
+    .unwrap();
+    let other = Product::read(&products, &other_id).await.unwrap().unwrap();
+    assert_eq!(other.sku, "CK-003");
+    assert_eq!(Product::list(&products).await.unwrap().len(), 2);
+
+    // Composite keys are tuples.
+    let page = Page {
+        site: "bakery".to_string(),
+        slug: "about".to_string(),
+        title: "About us".to_string(),
+    };
+    let page_id = page.create(&pages).await.unwrap();
+    assert_eq!(
+        page_id.as_raw(),
+        &("bakery".to_string(), "about".to_string())
+    );
+    let other_page_id = Page {
+        site: "cafe".to_string(),
+        slug: "about".to_string(),
+        title: "About the cafe".to_string(),
+    }
+    .create(&pages)
+    .await
+    .unwrap();
+    assert_eq!(Page::read(&pages, &page_id).await.unwrap(), Some(page));
+    assert_eq!(Page::list(&pages).await.unwrap().len(), 2);
+
+    Page::delete(&pages, &page_id).await.unwrap().unwrap();
+    assert!(Page::read(&pages, &page_id).await.unwrap().is_none());
+    assert!(Page::read(&pages, &other_page_id).await.unwrap().is_some());
+    assert!(Page::delete(&pages, &page_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn field_options() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    // Skipped fields have no column, and defaults and nullability are part of the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Batch>()
+    .build();
+    assert_eq!(
+        schema,
+        "CREATE TABLE Batch (loaves INTEGER NOT NULL, status TEXT NOT NULL DEFAULT 'proving', \
+         dozens INTEGER, Id INTEGER PRIMARY KEY AUTOINCREMENT);"
+    );
+
+    // `dozens` is a generated column, which can't be written to.
+    sqlx::raw_sql(
+        "
+            CREATE TABLE Batch (
+                loaves INTEGER,
+                status TEXT DEFAULT 'proving',
+                dozens INTEGER GENERATED ALWAYS AS (loaves / 12),
+                Id INTEGER PRIMARY KEY
+            );
+        ",
+    )
+    .execute(&db_pool)
+    .await
+    .unwrap();
+    let backing: storage_noodle_sql::SqlBacking<_, RawId> =
+        storage_noodle_sql::SqlBacking::new(db_pool.clone());
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // Read only columns are filled in by the database, and skipped fields are defaulted.
+    let batch_id = Batch {
+        loaves: 36,
+        status: "baked".to_string(),
+        dozens: 0,
+        oven: "left".to_string(),
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+    let batch = Batch {
+        loaves: 36,
+        status: "proving".to_string(),
+        dozens: 3,
+        oven: String::new(),
+    };
+    assert_eq!(Batch::read(&backing, &batch_id).await.unwrap(), Some(batch));
+
+    // Updates don't write read only columns either.
+    sqlx::query("UPDATE Batch SET status = 'baked'")
+        .execute(&db_pool)
+        .await
+        .unwrap();
+    Batch {
+        loaves: 24,
+        status: "burnt".to_string(),
+        dozens: 0,
+        oven: "right".to_string(),
+    }
+    .update(&backing, &batch_id)
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        Batch::list(&backing).await.unwrap(),
+        vec![(
+            batch_id,
+            Batch {
+                loaves: 24,
+                status: "baked".to_string(),
+                dozens: 2,
+                oven: String::new(),
+            }
+        )]
+    );
+}
+
+#[tokio::test]
+async fn indexes() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Cake>()
+    .build();
+    assert_eq!(
+        schema,
+        "CREATE TABLE Cake (code TEXT NOT NULL, shop TEXT NOT NULL, name TEXT NOT NULL, \
+         price INTEGER NOT NULL, Id INTEGER PRIMARY KEY AUTOINCREMENT);\
+         CREATE UNIQUE INDEX IF NOT EXISTS Cake_code_key ON Cake (code);\
+         CREATE INDEX IF NOT EXISTS Cake_price_idx ON Cake (price);\
+         CREATE UNIQUE INDEX IF NOT EXISTS Cake_shop_name_key ON Cake (shop, name);"
+    );
+    sqlx::raw_sql(&schema).execute(&db_pool).await.unwrap();
+    let backing: storage_noodle_sql::SqlBacking<_, RawId> =
+        storage_noodle_sql::SqlBacking::new(db_pool);
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    let cake = |code: &str, name: &str| Cake {
+        code: code.to_string(),
+        shop: "Leeds".to_string(),
+        name: name.to_string(),
+        price: 350,
+    };
+    let victoria_id = cake("VS", "victoria sponge")
+        .create(&backing)
+        .await
+        .unwrap();
+    let lemon_id = cake("LD", "lemon drizzle").create(&backing).await.unwrap();
+
+    // Creates and updates that duplicate a unique column are conflicts.
+    let error = cake("VS", "carrot").create(&backing).await.unwrap_err();
+    assert!(error.is_conflict());
+    let error = cake("LD", "victoria sponge")
+        .update(&backing, &lemon_id)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.constraint_violation().map(|violation| violation.kind),
+        Some(ConstraintKind::Unique)
+    );
+
+    // Creating with a new id can conflict too, but an existing id isn't an error.
+    let error = cake("VS", "carrot")
+        .create_with_id(&backing, &AssocId::new(100))
+        .await
+        .unwrap_err();
+    assert!(error.is_conflict());
+    assert!(
+        cake("CC", "carrot")
+            .create_with_id(&backing, &victoria_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(Cake::list(&backing).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn checks() {
+    // Set up the backing storage.
+    let db_pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Sqlite>::new(
+        storage_noodle_sql::schema::sqlite::generate_schema,
+    )
+    .add_type::<Tart>()
+    .build();
+    assert_eq!(
+        schema,
+        "CREATE TABLE Tart (flavour TEXT NOT NULL, price INTEGER NOT NULL CHECK (price >= 0), \
+         slices INTEGER NOT NULL, Id INTEGER PRIMARY KEY AUTOINCREMENT, \
+         CHECK (length(flavour) < 64), CHECK (slices <= price));"
+    );
+    sqlx::raw_sql(&schema).execute(&db_pool).await.unwrap();
+    let backing: storage_noodle_sql::SqlBacking<_, RawId> =
+        storage_noodle_sql::SqlBacking::new(db_pool);
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    let tart = |flavour: &str, price, slices| Tart {
+        flavour: flavour.to_string(),
+        price,
+        slices,
+    };
+    let tart_id = tart("bakewell", 300, 8).create(&backing).await.unwrap();
+
+    // Creates and updates that break a check constraint are check violations.
+    let error = tart("treacle", -1, 0).create(&backing).await.unwrap_err();
+    assert_eq!(
+        error.constraint_violation().map(|violation| violation.kind),
+        Some(ConstraintKind::Check)
+    );
+    assert!(!error.is_conflict());
+    let error = tart(&"custard ".repeat(8), 300, 8)
+        .create(&backing)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.constraint_violation().map(|violation| violation.kind),
+        Some(ConstraintKind::Check)
+    );
+    let error = tart("bakewell", 4, 8)
+        .update(&backing, &tart_id)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.constraint_violation().map(|violation| violation.kind),
+        Some(ConstraintKind::Check)
+    );
+    assert_eq!(
+        Tart::read(&backing, &tart_id).await.unwrap(),
+        Some(tart("bakewell", 300, 8))
+    );
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+#[storage_noodle_raw_id(RawId)]
+struct Cookie<RawId> {
+    flavour: String,
+    recipe: storage_noodle_traits::AssocId<Recipe, RawId>,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    storage_noodle_sql::Storable,
+    storage_noodle_sql::CreateWithId,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+struct Recipe {
+    ingredients: String,
+}
+
+impl References<Recipe, RawId> for Cookie<RawId> {
+    fn references(&self) -> Vec<&AssocId<Recipe, RawId>> {
+        vec![&self.recipe]
+    }
+}
+
+impl ReferencesMut<Recipe, RawId> for Cookie<RawId> {
+    fn references_mut(&mut self) -> Vec<&mut AssocId<Recipe, RawId>> {
+        vec![&mut self.recipe]
+    }
+}
+
+impl References<Recipe, RawId> for Pie<RawId> {
+    fn references(&self) -> Vec<&AssocId<Recipe, RawId>> {
+        vec![self.recipe.id()]
+    }
+}
+
+/// The backing storage that recipes enforce references in.
+type SqliteBacking = storage_noodle_sql::SqlBacking<sqlx::Sqlite, RawId>;
+
+impl Referenced<SqliteBacking> for Recipe {
+    type Error = sqlx::Error;
+
+    async fn on_delete(
+        storage: &SqliteBacking,
+        id: &AssocId<Self, RawId>,
+    ) -> Result<(), IntegrityError<Self::Error>> {
+        restrict::<Cookie<RawId>, Self, SqliteBacking>(storage, id).await?;
+        cascade::<Pie<RawId>, Self, SqliteBacking>(storage, id).await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+#[storage_noodle_raw_id(RawId)]
+#[storable(only(create, read))]
+struct Topping<RawId> {
+    name: String,
+    #[storage_noodle(on_delete = cascade)]
+    recipe: AssocId<Recipe, RawId>,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    storage_noodle_sql::Create,
+    storage_noodle_sql::Read,
+    storage_noodle_sql::SqlTable,
+    sqlx::FromRow,
+)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+#[storage_noodle_raw_id(RawId)]
+struct Tray<RawId> {
+    #[storage_noodle(on_delete = set_null)]
+    recipe: Option<AssocId<Recipe, RawId>>,
+}
+
 #[derive(
     Debug,
     PartialEq,
@@ -95,16 +1796,64 @@ async fn main() {
     sqlx::FromRow,
 )]
 #[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
-#[storage_noodle_raw_id(RawId)]
-struct Cookie<RawId> {
+#[storage_noodle(tenant = String)]
+struct Note {
+    text: String,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    storage_noodle_sql::Create,
+    storage_noodle_sql::CreateWithId,
+    storage_noodle_sql::SqlTable,
+)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+#[storage_noodle(tenant = String)]
+struct Badge {
+    #[storage_noodle(unique)]
+    code: String,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    storage_noodle_sql::Create,
+    storage_noodle_sql::Read,
+    storage_noodle_sql::Update,
+    storage_noodle_sql::Delete,
+    storage_noodle_sql::Restore,
+    storage_noodle_sql::Purge,
+    storage_noodle_sql::SqlTable,
+    sqlx::FromRow,
+)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+#[storage_noodle(soft_delete)]
+struct Biscuit {
     flavour: String,
-    recipe: storage_noodle_traits::AssocId<Recipe, RawId>,
 }
 
 #[derive(
     Debug,
     PartialEq,
     storage_noodle_sql::Create,
+    storage_noodle_sql::CreateWithTtl,
+    storage_noodle_sql::Read,
+    storage_noodle_sql::PurgeExpired,
+    storage_noodle_sql::SqlTable,
+    sqlx::FromRow,
+)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+#[storage_noodle(expires)]
+struct Session {
+    user: String,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    storage_noodle_sql::Create,
+    storage_noodle_sql::CreateWithTtl,
     storage_noodle_sql::Read,
     storage_noodle_sql::Update,
     storage_noodle_sql::Delete,
@@ -112,6 +1861,183 @@ struct Cookie<RawId> {
     sqlx::FromRow,
 )]
 #[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
-struct Recipe {
-    ingredients: String,
+#[storage_noodle(hooks, expires)]
+struct Comment {
+    text: String,
+}
+
+impl Comment {
+    /// Rejects empty comments.
+    fn validate(&self) -> Result<(), String> {
+        if self.text.is_empty() {
+            Err("empty comment".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Hooks for Comment {
+    type ValidationError = String;
+
+    fn before_create(&self) -> Result<(), Self::ValidationError> {
+        self.validate()
+    }
+
+    fn before_update<RawId>(
+        &self,
+        _id: &storage_noodle_traits::AssocId<Self, RawId>,
+    ) -> Result<(), Self::ValidationError> {
+        self.validate()
+    }
+}
+
+/// The number of labels created through [`HookedBacking`].
+static LABELS_CREATED: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+#[derive(
+    Debug,
+    PartialEq,
+    storage_noodle_sql::Create,
+    storage_noodle_sql::Read,
+    storage_noodle_sql::SqlTable,
+    sqlx::FromRow,
+)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+struct Label {
+    name: String,
+}
+
+impl Hooks for Label {
+    /// The length of the rejected name.
+    type ValidationError = usize;
+
+    fn before_create(&self) -> Result<(), Self::ValidationError> {
+        if self.name.len() > 8 {
+            Err(self.name.len())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn after_create<RawId>(&self, _id: &storage_noodle_traits::AssocId<Self, RawId>) {
+        LABELS_CREATED.fetch_add(1, core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    storage_noodle_sql::Create,
+    storage_noodle_sql::CreateWithId,
+    storage_noodle_sql::Read,
+    storage_noodle_sql::SqlTable,
+    sqlx::FromRow,
+)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, String)]
+#[storage_noodle(id_generator = storage_noodle_traits::id::UuidV7)]
+struct Event {
+    name: String,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    storage_noodle_sql::Create,
+    storage_noodle_sql::Read,
+    storage_noodle_sql::Delete,
+    storage_noodle_sql::List,
+    storage_noodle_sql::SqlTable,
+    sqlx::FromRow,
+)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+#[storage_noodle_raw_id(RawId)]
+struct Pie<RawId> {
+    recipe: Ref<Recipe, RawId>,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+#[storage_noodle(table = "bakeries", rename_all = "snake_case")]
+struct Bakery {
+    shop_name: String,
+    #[storage_noodle(rename = "town")]
+    location: String,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    storage_noodle_sql::Create,
+    storage_noodle_sql::Read,
+    storage_noodle_sql::Delete,
+    storage_noodle_sql::SqlTable,
+    sqlx::FromRow,
+)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+#[storage_noodle_raw_id(RawId)]
+#[storage_noodle(table = "loaves", rename_all = "camelCase", soft_delete)]
+#[sqlx(rename_all = "camelCase")]
+struct Loaf<RawId> {
+    bakery_id: AssocId<Bakery, RawId>,
+    loaf_kind: String,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+#[storage_noodle(id_column = "shelf_id")]
+struct Shelf {
+    label: String,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable, storage_noodle_sql::CreateWithId)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, String)]
+struct Product {
+    #[storage_noodle(primary_key)]
+    sku: String,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, (String, String))]
+struct Page {
+    #[storage_noodle(primary_key)]
+    site: String,
+    #[storage_noodle(primary_key)]
+    slug: String,
+    title: String,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+struct Batch {
+    loaves: i64,
+    #[storage_noodle(read_only, default = "'proving'")]
+    status: String,
+    #[storage_noodle(read_only, nullable = true)]
+    dozens: i64,
+    #[storage_noodle(skip)]
+    oven: String,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable, storage_noodle_sql::CreateWithId)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+#[storage_noodle(index(fields = [shop, name], unique))]
+struct Cake {
+    #[storage_noodle(unique)]
+    code: String,
+    shop: String,
+    name: String,
+    #[storage_noodle(index)]
+    price: i64,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, RawId)]
+#[storage_noodle(check = "length(flavour) < 64", check = "slices <= price")]
+struct Tart {
+    flavour: String,
+    #[storage_noodle(check = "price >= 0")]
+    price: i64,
+    slices: i64,
 }
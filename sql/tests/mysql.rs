@@ -0,0 +1,275 @@
+//! Integration test for SQL backing storage on mysql (or mariadb).
+//!
+//! The queries require `mysqld` to be on the path, or `DATABASE_URL` to point at an empty database
+//! (which can be on mariadb).
+
+use storage_noodle_traits::{
+    AssocId, Create, CreateWithId, CreateWithTtl, Delete, List, Purge, PurgeExpired, Read, Restore,
+    Update, audit::AuditedBacking, id::Snowflake, tenant::TenantScoped,
+};
+
+#[path = "utils/mysql.rs"]
+mod utils;
+
+/// The id type used for referencing items.
+type RawId = i64;
+
+/// The backing storage used by the tests.
+type MySqlBacking = storage_noodle_sql::SqlBacking<sqlx::MySql, RawId>;
+
+/// Generates the ids of new items.
+static SNOWFLAKE: Snowflake = Snowflake::new(1);
+
+/// Generates the schema of the test types.
+fn schema() -> String {
+    storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::MySql>::new(
+        storage_noodle_sql::schema::mysql::generate_schema,
+    )
+    .add_type::<Recipe>()
+    .add_type::<Order<RawId>>()
+    .add_type::<Note>()
+    .add_type::<Page>()
+    .add_type::<storage_noodle_sql::audit::AuditLog>()
+    .build()
+}
+
+#[test]
+fn schema_generation() {
+    // Identifiers are quoted, and ids are created by the database unless there is a generator.
+    let schema = schema();
+    assert!(schema.contains(
+        "CREATE TABLE `Recipe` (`ingredients` TEXT NOT NULL, \
+         `Id` BIGINT PRIMARY KEY AUTO_INCREMENT) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;"
+    ));
+    assert!(schema.contains("`ExpiresAt` BIGINT, `Id` BIGINT PRIMARY KEY)"));
+    assert!(schema.contains("`recipe` BIGINT NOT NULL, "));
+    assert!(schema.contains("FOREIGN KEY (`recipe`) REFERENCES `Recipe`(`Id`)"));
+    assert!(schema.contains("`TenantId` TEXT NOT NULL"));
+
+    // Keys and indexed columns get a bounded type, and composite keys, checks, and indexes are part
+    // of the table definition.
+    assert!(schema.contains(
+        "CREATE TABLE `Page` (`site` VARCHAR(255) NOT NULL, `slug` VARCHAR(255) NOT NULL, \
+         `title` VARCHAR(255) NOT NULL, PRIMARY KEY (`site`, `slug`), CHECK (site <> slug), \
+         INDEX `Page_title_idx` (`title`))"
+    ));
+
+    // The audit log's columns aren't reserved words.
+    assert!(
+        schema.contains(
+            "`Operation` TEXT NOT NULL, `BeforeJson` TEXT, `AfterJson` TEXT) ENGINE=InnoDB"
+        )
+    );
+
+    // Tables can use other options.
+    let options = storage_noodle_sql::schema::mysql::TableOptions {
+        engine: "MyISAM".to_string(),
+        charset: "latin1".to_string(),
+        collation: Some("latin1_bin".to_string()),
+    };
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::MySql>::new(|table| {
+        options.generate_schema(table)
+    })
+    .add_type::<Recipe>()
+    .build();
+    assert!(schema.ends_with(") ENGINE=MyISAM DEFAULT CHARSET=latin1 COLLATE=latin1_bin;"));
+}
+
+#[tokio::test]
+async fn main() {
+    // Start mysql.
+    let guard = utils::MysqlGuard::new().await.unwrap();
+    let db_pool = sqlx::mysql::MySqlPool::connect(&guard.url).await.unwrap();
+
+    // Execute the schema.
+    sqlx::raw_sql(&schema()).execute(&db_pool).await.unwrap();
+
+    // Set up the backing storage.
+    let backing = MySqlBacking::new(db_pool.clone());
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // Creates read the id of the new row back with `LAST_INSERT_ID()`.
+    let recipe = Recipe {
+        ingredients: "flour, butter, sugar".to_string(),
+    };
+    let recipe_id = recipe.create(&backing).await.unwrap();
+    assert_eq!(
+        Recipe::read(&backing, &recipe_id).await.unwrap(),
+        Some(recipe)
+    );
+    let other_recipe_id = Recipe {
+        ingredients: "oats".to_string(),
+    }
+    .create(&backing)
+    .await
+    .unwrap();
+    assert_ne!(recipe_id, other_recipe_id);
+
+    // Reserved words can be used as names.
+    let order = Order {
+        key: "shortbread".to_string(),
+        recipe: recipe_id,
+    };
+    let order_id = order.create(&backing).await.unwrap();
+    let order = Order {
+        key: "millionaire's shortbread".to_string(),
+        recipe: recipe_id,
+    };
+    order.update(&backing, &order_id).await.unwrap().unwrap();
+    assert_eq!(
+        Order::list(&backing).await.unwrap(),
+        vec![(order_id, order)]
+    );
+
+    Order::delete(&backing, &order_id).await.unwrap().unwrap();
+    assert!(Order::read(&backing, &order_id).await.unwrap().is_none());
+
+    // Missing items can't be updated or deleted.
+    let order = Order {
+        key: "ginger".to_string(),
+        recipe: recipe_id,
+    };
+    assert!(order.update(&backing, &order_id).await.unwrap().is_none());
+    assert!(Order::delete(&backing, &order_id).await.unwrap().is_none());
+
+    // Items can be created with a known id, but not over an existing one.
+    let id = AssocId::new(42);
+    let recipe = Recipe {
+        ingredients: "oats".to_string(),
+    };
+    recipe.create_with_id(&backing, &id).await.unwrap().unwrap();
+    assert!(
+        recipe
+            .create_with_id(&backing, &id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    audited(&backing, &db_pool).await;
+
+    // Client-side ids are used as they are.
+    let alice = TenantScoped::new(backing.clone(), "alice".to_string());
+    let bob = TenantScoped::new(backing, "bob".to_string());
+    let note = Note {
+        text: "preheat the oven".to_string(),
+    };
+    let note_id = note
+        .create_with_ttl(&alice, core::time::Duration::from_secs(60 * 60))
+        .await
+        .unwrap();
+    assert!(Note::read(&bob, &note_id).await.unwrap().is_none());
+    assert_eq!(Note::list(&alice).await.unwrap(), vec![(note_id, note)]);
+
+    Note::delete(&alice, &note_id).await.unwrap().unwrap();
+    assert!(Note::read(&alice, &note_id).await.unwrap().is_none());
+    Note::restore(&alice, &note_id).await.unwrap().unwrap();
+    assert!(Note::read(&alice, &note_id).await.unwrap().is_some());
+    Note::delete(&alice, &note_id).await.unwrap().unwrap();
+    Note::purge(&alice, &note_id).await.unwrap().unwrap();
+
+    let note_id = Note {
+        text: "take the cookies out".to_string(),
+    }
+    .create_with_ttl(&alice, core::time::Duration::ZERO)
+    .await
+    .unwrap();
+    assert!(Note::read(&alice, &note_id).await.unwrap().is_none());
+    assert_eq!(Note::purge_expired(&alice).await.unwrap(), 1);
+}
+
+/// Records changes in the audit log.
+async fn audited(backing: &MySqlBacking, db_pool: &sqlx::MySqlPool) {
+    let audited = AuditedBacking::new(
+        backing.clone(),
+        storage_noodle_sql::audit::SqlAuditSink::new(db_pool.clone()),
+        "alice".to_string(),
+    );
+    let recipe_id = Recipe {
+        ingredients: "flour, treacle".to_string(),
+    }
+    .create(&audited)
+    .await
+    .unwrap();
+    Recipe {
+        ingredients: "flour, treacle, ginger".to_string(),
+    }
+    .update(&audited, &recipe_id)
+    .await
+    .unwrap()
+    .unwrap();
+    let log: Vec<(String, Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT Operation, BeforeJson, AfterJson FROM AuditLog ORDER BY Id")
+            .fetch_all(db_pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        log,
+        [
+            (
+                "create".to_string(),
+                None,
+                Some(r#"{"ingredients":"flour, treacle"}"#.to_string())
+            ),
+            (
+                "update".to_string(),
+                Some(r#"{"ingredients":"flour, treacle"}"#.to_string()),
+                Some(r#"{"ingredients":"flour, treacle, ginger"}"#.to_string())
+            ),
+        ]
+    );
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    storage_noodle_sql::Storable,
+    storage_noodle_sql::CreateWithId,
+    serde::Serialize,
+)]
+#[storage_noodle_sql(sqlx::MySql, RawId)]
+struct Recipe {
+    ingredients: String,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::MySql, RawId)]
+#[storage_noodle_raw_id(RawId)]
+struct Order<RawId> {
+    key: String,
+    recipe: AssocId<Recipe, RawId>,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    storage_noodle_sql::Create,
+    storage_noodle_sql::CreateWithTtl,
+    storage_noodle_sql::Read,
+    storage_noodle_sql::Delete,
+    storage_noodle_sql::List,
+    storage_noodle_sql::Restore,
+    storage_noodle_sql::Purge,
+    storage_noodle_sql::PurgeExpired,
+    storage_noodle_sql::SqlTable,
+    sqlx::FromRow,
+)]
+#[storage_noodle_sql(sqlx::MySql, RawId)]
+#[storage_noodle(tenant = String, soft_delete, expires, id_generator = SNOWFLAKE)]
+struct Note {
+    text: String,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::MySql, (String, String))]
+#[storage_noodle(index(fields = [title]), check = "site <> slug")]
+struct Page {
+    #[storage_noodle(primary_key)]
+    site: String,
+    #[storage_noodle(primary_key)]
+    slug: String,
+    title: String,
+}
@@ -0,0 +1,339 @@
+//! Integration test for SQL backing storage on postgres.
+//!
+//! Requires `initdb` and `postgres` to be on the path, or `DATABASE_URL` to point at an empty
+//! database.
+
+use storage_noodle_sql::error::{ConstraintError, ConstraintKind, ConstraintViolation};
+use storage_noodle_traits::{
+    AssocId, Create, CreateWithId, CreateWithTtl, Delete, List, Purge, PurgeExpired, Read, Restore,
+    Update, id::Snowflake, tenant::TenantScoped,
+};
+
+#[path = "utils/postgres.rs"]
+mod utils;
+
+/// The id type used for referencing items.
+type RawId = i64;
+
+/// The backing storage used by the tests.
+type PgBacking = storage_noodle_sql::SqlBacking<sqlx::Postgres, RawId>;
+
+/// Generates the ids of new items.
+static SNOWFLAKE: Snowflake = Snowflake::new(1);
+
+#[tokio::test]
+async fn main() {
+    // Start postgres.
+    let guard = utils::PostgresGuard::new().await.unwrap();
+    let db_pool = sqlx::postgres::PgPool::connect(&guard.url).await.unwrap();
+
+    // Generate and execute the schema.
+    let schema = storage_noodle_sql::schema::SchemaBuilder::<_, sqlx::Postgres>::new(
+        storage_noodle_sql::schema::postgres::generate_schema,
+    )
+    .add_type::<Recipe>()
+    .add_type::<Cookie<RawId>>()
+    .add_type::<Note>()
+    .add_type::<Loaf>()
+    .add_type::<Page>()
+    .add_type::<Tin>()
+    .add_type::<Receipt>()
+    .build();
+    sqlx::raw_sql(&format!("CREATE SCHEMA bakery;{schema}"))
+        .execute(&db_pool)
+        .await
+        .unwrap();
+
+    // Set up the backing storage.
+    let backing = PgBacking::new(db_pool);
+
+    // ==========
+    // BEGIN TEST
+    // ==========
+
+    // Every CRUD operation binds its values to numbered placeholders.
+    let recipe = Recipe {
+        ingredients: "flour, butter, sugar".to_string(),
+    };
+    let recipe_id = recipe.create(&backing).await.unwrap();
+    assert_eq!(
+        Recipe::read(&backing, &recipe_id).await.unwrap(),
+        Some(recipe)
+    );
+
+    let cookie = Cookie {
+        flavour: "shortbread".to_string(),
+        recipe: recipe_id,
+    };
+    let cookie_id = cookie.create(&backing).await.unwrap();
+    let cookie = Cookie {
+        flavour: "millionaire's shortbread".to_string(),
+        recipe: recipe_id,
+    };
+    cookie.update(&backing, &cookie_id).await.unwrap().unwrap();
+    assert_eq!(
+        Cookie::list(&backing).await.unwrap(),
+        vec![(cookie_id, cookie)]
+    );
+
+    Cookie::delete(&backing, &cookie_id).await.unwrap().unwrap();
+    assert!(Cookie::read(&backing, &cookie_id).await.unwrap().is_none());
+
+    // Missing items can't be updated or deleted.
+    let cookie = Cookie {
+        flavour: "ginger".to_string(),
+        recipe: recipe_id,
+    };
+    assert!(cookie.update(&backing, &cookie_id).await.unwrap().is_none());
+    assert!(
+        Cookie::delete(&backing, &cookie_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Items can be created with a known id, but not over an existing one.
+    let id = AssocId::new(42);
+    let recipe = Recipe {
+        ingredients: "oats".to_string(),
+    };
+    recipe.create_with_id(&backing, &id).await.unwrap().unwrap();
+    assert!(
+        recipe
+            .create_with_id(&backing, &id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    generated_ids(&schema, &backing).await;
+    conflicts(&backing, id).await;
+    names_and_keys(&backing, id).await;
+
+    // Tenant scoped, soft deleted, and expiring items bind several values per query.
+    let alice = TenantScoped::new(backing.clone(), "alice".to_string());
+    let bob = TenantScoped::new(backing, "bob".to_string());
+    let note = Note {
+        text: "preheat the oven".to_string(),
+    };
+    let note_id = note
+        .create_with_ttl(&alice, core::time::Duration::from_secs(60 * 60))
+        .await
+        .unwrap();
+    assert!(Note::read(&bob, &note_id).await.unwrap().is_none());
+    assert!(Note::delete(&bob, &note_id).await.unwrap().is_none());
+    assert_eq!(Note::list(&alice).await.unwrap(), vec![(note_id, note)]);
+
+    Note::delete(&alice, &note_id).await.unwrap().unwrap();
+    assert!(Note::read(&alice, &note_id).await.unwrap().is_none());
+    Note::restore(&alice, &note_id).await.unwrap().unwrap();
+    assert!(Note::read(&alice, &note_id).await.unwrap().is_some());
+    Note::delete(&alice, &note_id).await.unwrap().unwrap();
+    Note::purge(&alice, &note_id).await.unwrap().unwrap();
+
+    let note_id = Note {
+        text: "take the cookies out".to_string(),
+    }
+    .create_with_ttl(&alice, core::time::Duration::ZERO)
+    .await
+    .unwrap();
+    assert!(Note::read(&alice, &note_id).await.unwrap().is_none());
+    assert_eq!(Note::purge_expired(&alice).await.unwrap(), 1);
+}
+
+/// Creates items with ids generated by the database.
+async fn generated_ids(schema: &str, backing: &PgBacking) {
+    // Ids are identity columns or defaults, unless they are generated client-side.
+    assert!(schema.contains("Id INT8 GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY"));
+    assert!(schema.contains("Id TEXT PRIMARY KEY DEFAULT gen_random_uuid()::text"));
+    assert!(schema.contains("recipe INT8 NOT NULL REFERENCES Recipe(Id), Id INT8 PRIMARY KEY"));
+
+    // Identity columns still accept explicit ids.
+    let first = Tin { size: 20 }.create(backing).await.unwrap();
+    let second = Tin { size: 24 }.create(backing).await.unwrap();
+    assert_eq!((*first.as_raw(), *second.as_raw()), (1, 2));
+    Tin { size: 30 }
+        .create_with_id(backing, &AssocId::new(4))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Tin::list(backing).await.unwrap().len(), 3);
+
+    // Later creates continue after the explicit id, rather than generating it again.
+    let third = Tin { size: 32 }.create(backing).await.unwrap();
+    assert_eq!(*third.as_raw(), 5);
+    Tin { size: 36 }
+        .create_with_id(backing, &AssocId::new(3))
+        .await
+        .unwrap()
+        .unwrap();
+    let fourth = Tin { size: 40 }.create(backing).await.unwrap();
+    assert_eq!(*fourth.as_raw(), 6);
+
+    // Default ids are returned by the insert.
+    let receipts =
+        storage_noodle_sql::SqlBacking::<sqlx::Postgres, String>::new(backing.pool.clone());
+    let receipt = Receipt { total: 450 };
+    let receipt_id = receipt.create(&receipts).await.unwrap();
+    assert_eq!(receipt_id.as_raw().len(), 36);
+    assert_eq!(
+        Receipt::read(&receipts, &receipt_id).await.unwrap(),
+        Some(receipt)
+    );
+}
+
+/// Creates items that conflict with a unique index, or break a check constraint.
+async fn conflicts(backing: &PgBacking, id: AssocId<Recipe, RawId>) {
+    // Postgres reports the (lower case) name of the violated index.
+    let recipe = Recipe {
+        ingredients: "oats".to_string(),
+    };
+    let error = recipe.create(backing).await.unwrap_err();
+    assert!(error.is_conflict());
+    assert_eq!(
+        error.constraint_violation().unwrap().constraint.as_deref(),
+        Some("recipe_ingredients_key")
+    );
+    let error = recipe
+        .create_with_id(backing, &AssocId::new(43))
+        .await
+        .unwrap_err();
+    assert!(error.is_conflict());
+    assert!(recipe.create_with_id(backing, &id).await.unwrap().is_none());
+
+    // Check constraints are named after their table and column.
+    let error = Recipe {
+        ingredients: String::new(),
+    }
+    .create(backing)
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error.constraint_violation(),
+        Some(ConstraintViolation {
+            kind: ConstraintKind::Check,
+            constraint: Some("recipe_ingredients_check".to_string()),
+        })
+    );
+}
+
+/// Creates items in a table in another schema, and with a composite key.
+async fn names_and_keys(backing: &PgBacking, id: AssocId<Recipe, RawId>) {
+    // Tables can be in another schema, with renamed columns.
+    let loaf = Loaf {
+        loaf_kind: "sourdough".to_string(),
+        recipe_id: id,
+    };
+    let loaf_id = loaf.create(backing).await.unwrap();
+    assert_eq!(Loaf::read(backing, &loaf_id).await.unwrap(), Some(loaf));
+    let kind: String = sqlx::query_scalar("SELECT loaf_kind FROM bakery.loaves")
+        .fetch_one(&backing.pool)
+        .await
+        .unwrap();
+    assert_eq!(kind, "sourdough");
+
+    // Composite keys bind every part of the id.
+    let pages = storage_noodle_sql::SqlBacking::<sqlx::Postgres, (String, String)>::new(
+        backing.pool.clone(),
+    );
+    let page = Page {
+        site: "bakery".to_string(),
+        slug: "about".to_string(),
+        title: "About us".to_string(),
+    };
+    let page_id = page.create(&pages).await.unwrap();
+    Page {
+        site: String::new(),
+        slug: String::new(),
+        title: "About the bakery".to_string(),
+    }
+    .update(&pages, &page_id)
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        Page::list(&pages).await.unwrap(),
+        vec![(
+            page_id.clone(),
+            Page {
+                site: "bakery".to_string(),
+                slug: "about".to_string(),
+                title: "About the bakery".to_string(),
+            }
+        )]
+    );
+    Page::delete(&pages, &page_id).await.unwrap().unwrap();
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable, storage_noodle_sql::CreateWithId)]
+#[storage_noodle_sql(sqlx::Postgres, RawId)]
+#[storage_noodle(id_generator = SNOWFLAKE)]
+struct Recipe {
+    #[storage_noodle(unique, check = "ingredients <> ''")]
+    ingredients: String,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::Postgres, RawId)]
+#[storage_noodle_raw_id(RawId)]
+#[storage_noodle(id_generator = SNOWFLAKE)]
+struct Cookie<RawId> {
+    flavour: String,
+    recipe: AssocId<Recipe, RawId>,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    storage_noodle_sql::Create,
+    storage_noodle_sql::CreateWithTtl,
+    storage_noodle_sql::Read,
+    storage_noodle_sql::Delete,
+    storage_noodle_sql::List,
+    storage_noodle_sql::Restore,
+    storage_noodle_sql::Purge,
+    storage_noodle_sql::PurgeExpired,
+    storage_noodle_sql::SqlTable,
+    sqlx::FromRow,
+)]
+#[storage_noodle_sql(sqlx::Postgres, RawId)]
+#[storage_noodle(tenant = String, soft_delete, expires, id_generator = SNOWFLAKE)]
+struct Note {
+    text: String,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::Postgres, RawId)]
+#[storage_noodle(
+    schema = "bakery",
+    table = "loaves",
+    rename_all = "snake_case",
+    id_generator = SNOWFLAKE
+)]
+struct Loaf {
+    loaf_kind: String,
+    recipe_id: AssocId<Recipe, RawId>,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::Postgres, (String, String))]
+struct Page {
+    #[storage_noodle(primary_key)]
+    site: String,
+    #[storage_noodle(primary_key)]
+    slug: String,
+    title: String,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable, storage_noodle_sql::CreateWithId)]
+#[storage_noodle_sql(sqlx::Postgres, RawId)]
+struct Tin {
+    size: i32,
+}
+
+#[derive(Debug, PartialEq, storage_noodle_sql::Storable)]
+#[storage_noodle_sql(sqlx::Postgres, String)]
+#[storage_noodle(id_default = "gen_random_uuid()::text")]
+struct Receipt {
+    total: i64,
+}
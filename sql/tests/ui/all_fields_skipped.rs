@@ -0,0 +1,10 @@
+//! A struct without any stored fields.
+
+#[derive(storage_noodle_sql::Create, storage_noodle_sql::SqlTable)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, u32)]
+struct Scratch {
+    #[storage_noodle(skip)]
+    note: String,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+//! `on_delete = set_null` on a column that can't be null.
+
+use storage_noodle_traits::AssocId;
+
+#[derive(storage_noodle_sql::Create, storage_noodle_sql::SqlTable)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, u32)]
+struct Recipe {
+    name: String,
+}
+
+#[derive(storage_noodle_sql::Create, storage_noodle_sql::SqlTable)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, u32)]
+struct Tray {
+    #[storage_noodle(on_delete = set_null)]
+    recipe: AssocId<Recipe, u32>,
+}
+
+fn main() {}
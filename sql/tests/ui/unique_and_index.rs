@@ -0,0 +1,10 @@
+//! `unique` and `index` on the same field.
+
+#[derive(storage_noodle_sql::Create, storage_noodle_sql::SqlTable)]
+#[storage_noodle_sql(sqlx::sqlite::Sqlite, u32)]
+struct Baker {
+    #[storage_noodle(unique, index)]
+    email: String,
+}
+
+fn main() {}
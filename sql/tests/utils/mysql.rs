@@ -0,0 +1,97 @@
+/// The port that the test database listens on.
+const PORT: u16 = 53_306;
+
+/// A database to run tests against, and the mysql server it is on (if it was started here).
+pub struct MysqlGuard {
+    /// The mysql server.
+    child: Option<std::process::Child>,
+
+    /// The data directory of the mysql server.
+    dir: std::path::PathBuf,
+
+    /// The url of the database.
+    pub url: String,
+}
+
+impl MysqlGuard {
+    /// Uses the database at `DATABASE_URL` if it is set, otherwise starts a mysql server in a
+    /// temporary directory and creates an empty database on it.
+    pub async fn new() -> Result<Self, Box<dyn core::error::Error>> {
+        let dir = std::env::temp_dir().join(format!("mysql-{}", std::process::id()));
+        let mut guard = if let Ok(url) = std::env::var("DATABASE_URL") {
+            Self {
+                child: None,
+                dir,
+                url,
+            }
+        } else {
+            let status = std::process::Command::new("mysqld")
+                .arg("--initialize-insecure")
+                .arg(format!("--datadir={}", dir.join("data").display()))
+                .stdout(std::process::Stdio::null())
+                .status()?;
+            if !status.success() {
+                return Err(format!("mysqld --initialize-insecure failed: {status}").into());
+            }
+
+            let child = std::process::Command::new("mysqld")
+                .arg(format!("--datadir={}", dir.join("data").display()))
+                .arg(format!("--socket={}", dir.join("mysqld.sock").display()))
+                .arg("--bind-address=127.0.0.1")
+                .arg(format!("--port={PORT}"))
+                .arg("--mysqlx=OFF")
+                .spawn()?;
+
+            Self {
+                child: Some(child),
+                dir,
+                url: format!("mysql://root@127.0.0.1:{PORT}/storage_noodle"),
+            }
+        };
+
+        // A server started here doesn't have the test database yet, so wait for the server itself.
+        let server_url = if guard.child.is_some() {
+            format!("mysql://root@127.0.0.1:{PORT}")
+        } else {
+            guard.url.clone()
+        };
+        let wait_for_mysql = async {
+            loop {
+                if let Ok(pool) = sqlx::mysql::MySqlPool::connect(&server_url).await {
+                    break pool;
+                }
+                tokio::time::sleep(core::time::Duration::from_millis(100)).await;
+            }
+        };
+        let pool =
+            match tokio::time::timeout(core::time::Duration::from_secs(30), wait_for_mysql).await {
+                Ok(pool) => pool,
+                Err(e) => {
+                    guard.stop();
+                    return Err(format!("Timed out waiting for mysql: {e}").into());
+                }
+            };
+        if guard.child.is_some() {
+            sqlx::raw_sql("CREATE DATABASE storage_noodle")
+                .execute(&pool)
+                .await?;
+        }
+
+        Ok(guard)
+    }
+
+    /// Stops the mysql server (if it was started here), and removes its data directory.
+    fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            child.kill().unwrap();
+            child.wait().unwrap();
+            std::fs::remove_dir_all(&self.dir).unwrap();
+        }
+    }
+}
+
+impl Drop for MysqlGuard {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
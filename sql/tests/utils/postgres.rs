@@ -0,0 +1,83 @@
+/// The port that the test database listens on.
+const PORT: u16 = 54_329;
+
+/// A database to run tests against, and the postgres server it is on (if it was started here).
+pub struct PostgresGuard {
+    /// The postgres server.
+    child: Option<std::process::Child>,
+
+    /// The data directory of the postgres server.
+    dir: std::path::PathBuf,
+
+    /// The url of the database.
+    pub url: String,
+}
+
+impl PostgresGuard {
+    /// Uses the database at `DATABASE_URL` if it is set, otherwise starts a postgres server in a
+    /// temporary directory.
+    pub async fn new() -> Result<Self, Box<dyn core::error::Error>> {
+        let dir = std::env::temp_dir().join(format!("postgres-{}", std::process::id()));
+        let mut guard = if let Ok(url) = std::env::var("DATABASE_URL") {
+            Self {
+                child: None,
+                dir,
+                url,
+            }
+        } else {
+            let status = std::process::Command::new("initdb")
+                .arg("--auth=trust")
+                .arg("--username=postgres")
+                .arg("-D")
+                .arg(&dir)
+                .stdout(std::process::Stdio::null())
+                .status()?;
+            if !status.success() {
+                return Err(format!("initdb failed: {status}").into());
+            }
+
+            let child = std::process::Command::new("postgres")
+                .arg("-D")
+                .arg(&dir)
+                .arg("-k")
+                .arg(&dir)
+                .args(["-h", "127.0.0.1", "-p", &PORT.to_string()])
+                .spawn()?;
+
+            Self {
+                child: Some(child),
+                dir,
+                url: format!("postgres://postgres@127.0.0.1:{PORT}/postgres"),
+            }
+        };
+
+        let wait_for_postgres = async {
+            while sqlx::postgres::PgPool::connect(&guard.url).await.is_err() {
+                tokio::time::sleep(core::time::Duration::from_millis(100)).await;
+            }
+        };
+        if let Err(e) =
+            tokio::time::timeout(core::time::Duration::from_secs(10), wait_for_postgres).await
+        {
+            guard.stop();
+            return Err(format!("Timed out waiting for postgres: {e}").into());
+        }
+
+        Ok(guard)
+    }
+
+    /// Stops the postgres server (if it was started here), and removes its data directory.
+    fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            child.kill().unwrap();
+            child.wait().unwrap();
+            std::fs::remove_dir_all(&self.dir).unwrap();
+        }
+    }
+}
+
+impl Drop for PostgresGuard {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -103,6 +103,415 @@ impl SqlAttr {
     }
 }
 
+/// Struct-level options, parsed from `storage_noodle` attributes.
+#[derive(Default, Clone)]
+pub struct Options {
+    /// The tenant id type (if the struct is tenant scoped).
+    pub tenant: Option<syn::Type>,
+
+    /// Whether deletes leave a tombstone instead of removing the row.
+    pub soft_delete: bool,
+
+    /// Whether rows can expire.
+    pub expires: bool,
+
+    /// Whether creates, updates, and deletes run the struct's `Hooks`.
+    pub hooks: bool,
+
+    /// The `IdGenerator` that creates ids client-side (if the database doesn't create them).
+    pub id_generator: Option<syn::Expr>,
+
+    /// The SQL expression that the database creates ids with (if they aren't auto-incremented).
+    pub id_default: Option<String>,
+
+    /// The table name (if it isn't the struct name).
+    pub table: Option<String>,
+
+    /// The schema that the table is in (if it isn't the default one).
+    pub schema: Option<String>,
+
+    /// The rule that column names are made with (if they aren't the field names).
+    pub rename_all: Option<RenameRule>,
+
+    /// The name of the id column (if it isn't made from `ID_FIELD_NAME`).
+    pub id_column: Option<String>,
+
+    /// The indexes on several fields.
+    pub indexes: Vec<Index>,
+
+    /// The SQL conditions that every row must meet.
+    pub checks: Vec<String>,
+}
+
+/// An index on several fields, from `index(fields = [...])`.
+#[derive(Clone)]
+pub struct Index {
+    /// The indexed fields (in order).
+    pub fields: Vec<syn::Ident>,
+
+    /// Whether the index is unique.
+    pub unique: bool,
+}
+
+impl Index {
+    /// Parses the nested options of an `index(...)` option.
+    fn parse(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Self> {
+        let mut fields = None;
+        let mut unique = false;
+        meta.parse_nested_meta(|meta| {
+            if meta.path.is_ident("fields") {
+                let value = meta.value()?;
+                let content;
+                syn::bracketed!(content in value);
+                let list = content
+                    .parse_terminated(<syn::Ident as syn::parse::Parse>::parse, syn::Token![,])?;
+                fields = Some(list.into_iter().collect::<Vec<_>>());
+                Ok(())
+            } else if meta.path.is_ident("unique") {
+                unique = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `fields` or `unique`"))
+            }
+        })?;
+
+        match fields {
+            Some(fields) if !fields.is_empty() => Ok(Self { fields, unique }),
+            _ => Err(meta.error("an index needs `fields = [...]`")),
+        }
+    }
+}
+
+impl Options {
+    /// Parse the options from every `storage_noodle` attribute on an item.
+    pub fn from_item(item: &ItemStruct) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for attr in &item.attrs {
+            if !attr.path().is_ident("storage_noodle") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("tenant") {
+                    options.tenant = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("soft_delete") {
+                    options.soft_delete = true;
+                    Ok(())
+                } else if meta.path.is_ident("expires") {
+                    options.expires = true;
+                    Ok(())
+                } else if meta.path.is_ident("hooks") {
+                    options.hooks = true;
+                    Ok(())
+                } else if meta.path.is_ident("id_generator") {
+                    options.id_generator = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("id_default") {
+                    options.id_default = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("table") {
+                    options.table = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("schema") {
+                    options.schema = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("id_column") {
+                    options.id_column = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("rename_all") {
+                    options.rename_all = Some(RenameRule::parse(&meta.value()?.parse()?)?);
+                    Ok(())
+                } else if meta.path.is_ident("index") {
+                    options.indexes.push(Index::parse(&meta)?);
+                    Ok(())
+                } else if meta.path.is_ident("check") {
+                    let check = meta.value()?.parse::<syn::LitStr>()?.value();
+                    options.checks.push(check);
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown `storage_noodle` option"))
+                }
+            })?;
+        }
+
+        if options.id_generator.is_some() && options.id_default.is_some() {
+            return Err(syn::Error::new_spanned(
+                &item.ident,
+                "`id_generator` and `id_default` can't be used together",
+            ));
+        }
+
+        Ok(options)
+    }
+
+    /// The table name: the `table` option, or the struct name.
+    pub fn table_name(&self, item: &ItemStruct) -> String {
+        self.table.clone().unwrap_or_else(|| item.ident.to_string())
+    }
+
+    /// The name of the id column (if the id isn't stored in `primary_key` fields).
+    pub fn id_column_name(&self) -> String {
+        self.id_column
+            .clone()
+            .unwrap_or_else(|| self.column_name(crate::sql::ID_FIELD_NAME))
+    }
+
+    /// The name of a column, after applying the `rename_all` rule (if any).
+    pub fn column_name(&self, name: &str) -> String {
+        self.rename_all
+            .map_or_else(|| name.to_string(), |rule| rule.apply(name))
+    }
+}
+
+/// The rules that `rename_all` can rename columns with.
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+    /// `lowercase`.
+    Lower,
+
+    /// `UPPERCASE`.
+    Upper,
+
+    /// `PascalCase`.
+    Pascal,
+
+    /// `camelCase`.
+    Camel,
+
+    /// `snake_case`.
+    Snake,
+
+    /// `SCREAMING_SNAKE_CASE`.
+    ScreamingSnake,
+}
+
+impl RenameRule {
+    /// Parse a rule from its name.
+    fn parse(name: &syn::LitStr) -> syn::Result<Self> {
+        match name.value().as_str() {
+            "lowercase" => Ok(Self::Lower),
+            "UPPERCASE" => Ok(Self::Upper),
+            "PascalCase" => Ok(Self::Pascal),
+            "camelCase" => Ok(Self::Camel),
+            "snake_case" => Ok(Self::Snake),
+            "SCREAMING_SNAKE_CASE" => Ok(Self::ScreamingSnake),
+            _ => Err(syn::Error::new_spanned(
+                name,
+                "expected one of `lowercase`, `UPPERCASE`, `PascalCase`, `camelCase`, `snake_case`, or `SCREAMING_SNAKE_CASE`",
+            )),
+        }
+    }
+
+    /// Renames a `snake_case` or `PascalCase` name.
+    fn apply(self, name: &str) -> String {
+        // Split the name into words, at underscores and the start of capitalised words.
+        let mut words = vec![String::new()];
+        for c in name.chars() {
+            let last = words.last().map(String::as_str).unwrap_or_default();
+            if c == '_' {
+                words.push(String::new());
+                continue;
+            }
+            if c.is_uppercase() && !last.is_empty() && !last.ends_with(char::is_uppercase) {
+                words.push(String::new());
+            }
+            if let Some(word) = words.last_mut() {
+                word.push(c);
+            }
+        }
+        words.retain(|word| !word.is_empty());
+
+        // Capitalises a word.
+        let capitalise = |word: &String| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect()
+            })
+        };
+
+        match self {
+            Self::Lower => words.concat().to_lowercase(),
+            Self::Upper => words.concat().to_uppercase(),
+            Self::Pascal => words.iter().map(capitalise).collect(),
+            Self::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    if i == 0 {
+                        word.to_lowercase()
+                    } else {
+                        capitalise(word)
+                    }
+                })
+                .collect(),
+            Self::Snake => words.join("_").to_lowercase(),
+            Self::ScreamingSnake => words.join("_").to_uppercase(),
+        }
+    }
+}
+
+/// Field-level options, parsed from `storage_noodle` attributes.
+#[derive(Default, Clone)]
+pub struct FieldOptions {
+    /// The `OnDelete` variant of the field's foreign key (if it was set).
+    pub on_delete: Option<syn::Ident>,
+
+    /// Whether the field shouldn't get a foreign key, even if it is an `AssocId`.
+    pub no_foreign_key: bool,
+
+    /// The column name (if it isn't made from the field name).
+    pub rename: Option<String>,
+
+    /// Whether the field is (part of) the primary key, instead of a separate id column.
+    pub primary_key: bool,
+
+    /// How the field is stored.
+    pub kind: FieldKind,
+
+    /// The SQL expression that the column defaults to in the schema (if any).
+    pub default: Option<String>,
+
+    /// Whether the column is nullable (if it isn't inferred from whether the field is an
+    /// `Option`).
+    pub nullable: Option<bool>,
+
+    /// Whether the column has an index of its own (`Some(true)` if the index is unique).
+    pub index: Option<bool>,
+
+    /// The SQL condition that the column's values must meet (if any).
+    pub check: Option<String>,
+}
+
+/// How a field is stored.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// The field is read from and written to its column.
+    #[default]
+    ReadWrite,
+
+    /// The field is read from its column, but never written (the database populates it).
+    ReadOnly,
+
+    /// The field isn't stored, and is filled with `Default::default()` on read.
+    Skip,
+}
+
+impl FieldOptions {
+    /// Parse the options from every `storage_noodle` attribute on a field.
+    pub fn from_field(field: &syn::Field) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for attr in &field.attrs {
+            if !attr.path().is_ident("storage_noodle") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("on_delete") {
+                    let action: syn::Ident = meta.value()?.parse()?;
+                    let variant = match action.to_string().as_str() {
+                        "no_action" => "NoAction",
+                        "restrict" => "Restrict",
+                        "cascade" => "Cascade",
+                        "set_null" => "SetNull",
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                action,
+                                "expected one of `no_action`, `restrict`, `cascade`, or `set_null`",
+                            ));
+                        }
+                    };
+                    options.on_delete = Some(syn::Ident::new(variant, action.span()));
+                    Ok(())
+                } else if meta.path.is_ident("no_foreign_key") {
+                    options.no_foreign_key = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    options.rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("primary_key") {
+                    options.primary_key = true;
+                    Ok(())
+                } else if meta.path.is_ident("read_only") || meta.path.is_ident("skip") {
+                    if options.kind != FieldKind::ReadWrite {
+                        return Err(meta.error("a field can't be both `read_only` and `skip`"));
+                    }
+                    options.kind = if meta.path.is_ident("skip") {
+                        FieldKind::Skip
+                    } else {
+                        FieldKind::ReadOnly
+                    };
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    options.default = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("index") || meta.path.is_ident("unique") {
+                    let unique = meta.path.is_ident("unique");
+                    if options.index.is_some_and(|index| index != unique) {
+                        return Err(meta.error("a field can't be both `index` and `unique`"));
+                    }
+                    options.index = Some(unique);
+                    Ok(())
+                } else if meta.path.is_ident("check") {
+                    options.check = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("nullable") {
+                    options.nullable = Some(meta.value()?.parse::<syn::LitBool>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown `storage_noodle` field option"))
+                }
+            })?;
+        }
+
+        if options.primary_key && options.kind != FieldKind::ReadWrite {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`primary_key` fields can't be `read_only` or `skip`",
+            ));
+        }
+
+        Ok(options)
+    }
+}
+
+/// Whether a type is an `Option`.
+pub fn is_option(ty: &syn::Type) -> bool {
+    let syn::Type::Path(type_path) = ty else {
+        return false;
+    };
+    type_path
+        .path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Option")
+}
+
+/// Returns the `T` of an `AssocId<T, _>` or `Ref<T, _>` type (optionally wrapped in an `Option`).
+pub fn referenced_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    let syn::GenericArgument::Type(first) = arguments.args.first()? else {
+        return None;
+    };
+
+    match segment.ident.to_string().as_str() {
+        "Option" => referenced_type(first),
+        "AssocId" | "Ref" => Some(first),
+        _ => None,
+    }
+}
+
 /// Extracts a type generic from the `storage_noodle_raw_id` attribute.
 pub fn raw_id_attr(item: &ItemStruct) -> Option<Result<syn::Ident, syn::Error>> {
     item.attrs.iter().find_map(|attr| {
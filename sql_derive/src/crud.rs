@@ -24,7 +24,7 @@ pub fn delete(item: &syn::ItemStruct) -> TokenStream {
 
 /// Per-attribute implementation for [`create`].
 fn create_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Type) -> TokenStream {
-    let syn::ItemStruct { ident, fields, .. } = item.clone();
+    let syn::ItemStruct { ident, .. } = item.clone();
 
     // Split generics.
     let (impl_generics, type_generics, where_clause) =
@@ -33,68 +33,52 @@ fn create_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Typ
             Err(e) => return e.to_compile_error(),
         };
 
-    // The table name.
-    let table = ident.to_string();
-
-    // List of columns.
-    let columns = crate::sql::Column::from_fields(&fields);
-
-    // The SQL query to run.
-    let query = {
-        let query = format!(
-            "
-                INSERT INTO {} ({})
-                VALUES ({})
-                RETURNING {};
-            ",
-            table,
-            columns
-                .iter()
-                .map(|c| c.name.clone())
-                .collect::<Vec<_>>()
-                .join(", "), // List of column names (in order).
-            (0..columns.len())
-                .map(|_| "?")
-                .collect::<Vec<_>>()
-                .join(", "), // List of "?" - to be filled in with bind calls.
-            crate::sql::ID_FIELD_NAME,
-        );
-        syn::LitStr::new(&query, proc_macro2::Span::mixed_site())
+    // The table description.
+    let table = match crate::attr::Options::from_item(item)
+        .and_then(|options| crate::sql::Table::new(item, &options, backing_db, raw_id))
+    {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
     };
-
-    // List of `.bind()` calls to run on the query.
-    let bind_calls: TokenStream = columns
-        .iter()
-        .map(|column| {
-            let field = &column.ident;
-            quote! {.bind(&self.#field)}
-        })
-        .collect();
+    let storage = &table.storage;
+
+    // Build & execute the query, getting the raw id of the new item back.
+    let generate_id = table.generate_id();
+    let insert = table.insert_returning_id(&table.create_columns(), &table.create_binds());
+
+    // The method body, wrapped in hook calls (if any).
+    let (error, body) = table.with_hooks(
+        quote! {
+            #generate_id
+
+            //= traits/spec.md#create-trait
+            //# * In the case of a failure, the future MUST return `Err()`.
+            //= traits/spec.md#create-trait
+            //# * In the case of a success, the future MUST return `Ok(AssocId<Self, RawId>)` - where the `AssocId` holds the Id of the newly created item.
+            #insert.map(::storage_noodle_sql::macro_helpers::AssocId::new)
+        },
+        &quote! {
+            ::storage_noodle_sql::macro_helpers::hooks::Hooks::before_create(self)
+        },
+        &quote! {
+            ::storage_noodle_sql::macro_helpers::hooks::Hooks::after_create(self, &result)
+        },
+    );
 
     // Implement the trait.
     quote! {
-        impl #impl_generics ::storage_noodle_sql::macro_helpers::Create<::storage_noodle_sql::SqlBacking<#backing_db, #raw_id>> for #ident #type_generics #where_clause
+        impl #impl_generics ::storage_noodle_sql::macro_helpers::Create<#storage> for #ident #type_generics #where_clause
         {
-            type Error = ::sqlx::Error;
+            type Error = #error;
 
             fn create<'a>(
                 &'a self,
-                storage: impl ::core::ops::Deref<Target = ::storage_noodle_sql::SqlBacking<#backing_db, #raw_id>>
+                storage: impl ::core::ops::Deref<Target = #storage>
                 + ::core::marker::Send
                 + 'a,
             ) -> impl ::core::future::Future<Output = ::core::result::Result<::storage_noodle_sql::macro_helpers::AssocId<Self, #raw_id>, Self::Error>> + ::core::marker::Send {
                 async move {
-                    // Build the query.
-                    let query = ::sqlx::query_scalar(#query)#bind_calls;
-
-                    // Get the raw id back from the query.
-                    //= traits/spec.md#create-trait
-                    //# * In the case of a failure, the future MUST return `Err()`.
-                    let raw = query.fetch_one(&storage.pool).await?;
-
-                    //= traits/spec.md#create-trait
-                    //# * In the case of a success, the future MUST return `Ok(AssocId<Self, RawId>)` - where the `AssocId` holds the Id of the newly created item.
-                    Ok(::storage_noodle_sql::macro_helpers::AssocId::new(raw))
+                    #body
                 }
             }
         }
@@ -103,7 +87,7 @@ fn create_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Typ
 
 /// Per-attribute implementation for [`read`].
 fn read_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Type) -> TokenStream {
-    let syn::ItemStruct { ident, fields, .. } = item.clone();
+    let syn::ItemStruct { ident, .. } = item.clone();
 
     // Split generics.
     let (impl_generics, type_generics, where_clause) =
@@ -112,47 +96,55 @@ fn read_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Type)
             Err(e) => return e.to_compile_error(),
         };
 
-    // The table name.
-    let table = ident.to_string();
-
-    // List of columns.
-    let columns = crate::sql::Column::from_fields(&fields);
+    // The table description.
+    let table = match crate::attr::Options::from_item(item)
+        .and_then(|options| crate::sql::Table::new(item, &options, backing_db, raw_id))
+    {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
+    };
+    let storage = &table.storage;
+    let pool = &table.pool;
 
     // The SQL query to run.
     let query = {
         let query = format!(
             "
                 SELECT {} FROM {}
-                WHERE {}=?;
+                WHERE {};
             ",
-            columns
+            table
+                .columns
                 .iter()
-                .map(|c| c.name.clone())
+                .map(|c| table.quote(&c.name))
                 .collect::<Vec<_>>()
                 .join(", "), // List of column names (in order).
-            table,
-            crate::sql::ID_FIELD_NAME,
+            table.quoted_name(),
+            table.filter(),
         );
-        syn::LitStr::new(&query, proc_macro2::Span::mixed_site())
+        table.query(&query)
     };
 
+    // List of `.bind()` calls that select the item.
+    let filter_binds = table.filter_binds();
+
     // Implement the trait.
     quote! {
-        impl #impl_generics ::storage_noodle_sql::macro_helpers::Read<::storage_noodle_sql::SqlBacking<#backing_db, #raw_id>> for #ident #type_generics #where_clause
+        impl #impl_generics ::storage_noodle_sql::macro_helpers::Read<#storage> for #ident #type_generics #where_clause
         {
             type Error = ::sqlx::Error;
 
             fn read(
-                storage: impl ::core::ops::Deref<Target = ::storage_noodle_sql::SqlBacking<#backing_db, #raw_id>>
+                storage: impl ::core::ops::Deref<Target = #storage>
                 + ::core::marker::Send,
                 id: impl ::core::ops::Deref<Target = ::storage_noodle_sql::macro_helpers::AssocId<Self, #raw_id>> + ::core::marker::Send
             ) -> impl ::core::future::Future<Output = ::core::result::Result<core::option::Option<Self>, Self::Error>> + ::core::marker::Send {
                 async move {
                     // Build the query.
-                    let query = ::sqlx::query_as(#query).bind(id.as_raw());
+                    let query = ::sqlx::query_as(#query)#filter_binds;
 
                     // Get the row back from the query.
-                    let result = query.fetch_one(&storage.pool).await;
+                    let result = query.fetch_one(&#pool).await;
 
                     match result {
                         //= traits/spec.md#read-trait
@@ -175,7 +167,7 @@ fn read_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Type)
 
 /// Per-attribute implementation for [`update`].
 fn update_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Type) -> TokenStream {
-    let syn::ItemStruct { ident, fields, .. } = item.clone();
+    let syn::ItemStruct { ident, .. } = item.clone();
 
     // Split generics.
     let (impl_generics, type_generics, where_clause) =
@@ -184,11 +176,27 @@ fn update_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Typ
             Err(e) => return e.to_compile_error(),
         };
 
-    // The table name.
-    let table = ident.to_string();
+    // The table description.
+    let table = match crate::attr::Options::from_item(item)
+        .and_then(|options| crate::sql::Table::new(item, &options, backing_db, raw_id))
+    {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
+    };
+    let storage = &table.storage;
+    let pool = &table.pool;
 
-    // List of columns.
-    let columns = crate::sql::Column::from_fields(&fields);
+    // The columns to set. If every column is part of the primary key, there is nothing to set, but
+    // the statement still has to match the row.
+    let mut assignments: Vec<_> = table
+        .value_columns()
+        .iter()
+        .map(|column| format!("{}=?", table.quote(&column.name)))
+        .collect();
+    if assignments.is_empty() {
+        let id_column = table.quote(&table.id_columns()[0]);
+        assignments.push(format!("{id_column}={id_column}"));
+    }
 
     // The SQL query to run.
     let query = {
@@ -196,61 +204,67 @@ fn update_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Typ
             "
                 UPDATE {}
                 SET {}
-                WHERE {}=?;
+                WHERE {};
             ",
-            table,
-            columns
-                .iter()
-                .map(|column| { format!("{}=?", column.name) })
-                .collect::<Vec<_>>()
-                .join(", "),
-            crate::sql::ID_FIELD_NAME,
+            table.quoted_name(),
+            assignments.join(", "),
+            table.filter(),
         );
-        syn::LitStr::new(&query, proc_macro2::Span::mixed_site())
+        table.query(&query)
     };
 
     // List of `.bind()` calls to run on the query.
-    let bind_calls: TokenStream = columns
-        .iter()
-        .map(|column| {
-            let field = &column.ident;
-            quote! {.bind(&self.#field)}
-        })
-        .collect();
+    let bind_calls = table.value_binds();
+    let filter_binds = table.filter_binds();
+
+    // The method body, wrapped in hook calls (if any).
+    let (error, body) = table.with_hooks(
+        quote! {
+            // Build the query.
+            let query = ::sqlx::query(#query)#bind_calls #filter_binds;
+
+            // Execute the query.
+            let result = query.execute(&#pool).await;
+
+            match result {
+                //= traits/spec.md#update-trait
+                //# * In the case of a partial success, where the operation succeeded, but the item doesn't exist, the future MUST return `Ok(None)`.
+                Ok(result) if result.rows_affected() == 0 => Ok(None),
+
+                //= traits/spec.md#update-trait
+                //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
+                Ok(_) => Ok(Some(())),
+
+                //= traits/spec.md#update-trait
+                //# * In the case of a failure, the future MUST return `Err()`.
+                Err(e) => Err(e),
+            }
+        },
+        &quote! {
+            ::storage_noodle_sql::macro_helpers::hooks::Hooks::before_update(self, &*id)
+        },
+        &quote! {
+            if result.is_some() {
+                ::storage_noodle_sql::macro_helpers::hooks::Hooks::after_update(self, &*id);
+            }
+        },
+    );
 
     // Implement the trait.
     quote! {
-        impl #impl_generics ::storage_noodle_sql::macro_helpers::Update<::storage_noodle_sql::SqlBacking<#backing_db, #raw_id>> for #ident #type_generics #where_clause
+        impl #impl_generics ::storage_noodle_sql::macro_helpers::Update<#storage> for #ident #type_generics #where_clause
         {
-            type Error = ::sqlx::Error;
+            type Error = #error;
 
             fn update<'a>(
                 &'a self,
-                storage: impl ::core::ops::Deref<Target = ::storage_noodle_sql::SqlBacking<#backing_db, #raw_id>>
+                storage: impl ::core::ops::Deref<Target = #storage>
                 + ::core::marker::Send
                 + 'a,
                 id: impl ::core::ops::Deref<Target = ::storage_noodle_sql::macro_helpers::AssocId<Self, #raw_id>> + ::core::marker::Send
             ) -> impl ::core::future::Future<Output = ::core::result::Result<::core::option::Option<()>, Self::Error>> + ::core::marker::Send {
                 async move {
-                    // Build the query.
-                    let query = ::sqlx::query(#query)#bind_calls.bind(id.as_raw());
-
-                    // Execute the query.
-                    let result = query.execute(&storage.pool).await;
-
-                    match result {
-                        //= traits/spec.md#update-trait
-                        //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
-                        Ok(_) => Ok(Some(())),
-
-                        //= traits/spec.md#update-trait
-                        //# * In the case of a partial success, where the operation succeeded, but the item doesn't exist, the future MUST return `Ok(None)`.
-                        Err(::sqlx::Error::RowNotFound) => Ok(None),
-
-                        //= traits/spec.md#update-trait
-                        //# * In the case of a failure, the future MUST return `Err()`.
-                        Err(e) => Err(e),
-                    }
+                    #body
                 }
             }
         }
@@ -268,50 +282,93 @@ fn delete_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Typ
             Err(e) => return e.to_compile_error(),
         };
 
-    // The table name.
-    let table = ident.to_string();
+    // The table description.
+    let table = match crate::attr::Options::from_item(item)
+        .and_then(|options| crate::sql::Table::new(item, &options, backing_db, raw_id))
+    {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
+    };
+    let storage = &table.storage;
+    let pool = &table.pool;
 
-    // The SQL query to run.
+    // The SQL query to run. Soft deletes only set the tombstone.
     let query = {
-        let query = format!(
-            "
-                DELETE FROM {}
-                WHERE {}=?;
-            ",
-            table,
-            crate::sql::ID_FIELD_NAME,
-        );
-        syn::LitStr::new(&query, proc_macro2::Span::mixed_site())
+        let query = if table.options.soft_delete {
+            format!(
+                "
+                    UPDATE {}
+                    SET {}=?
+                    WHERE {};
+                ",
+                table.quoted_name(),
+                table.column(crate::sql::DELETED_AT_FIELD_NAME),
+                table.filter(),
+            )
+        } else {
+            format!(
+                "
+                    DELETE FROM {}
+                    WHERE {};
+                ",
+                table.quoted_name(),
+                table.filter(),
+            )
+        };
+        table.query(&query)
     };
 
+    // List of `.bind()` calls that set the tombstone (if any) and select the item.
+    let filter_binds = if table.options.soft_delete {
+        let filter_binds = table.filter_binds();
+        quote! {.bind(::storage_noodle_sql::macro_helpers::unix_now())#filter_binds}
+    } else {
+        table.filter_binds()
+    };
+
+    // The method body, wrapped in hook calls (if any).
+    let (error, body) = table.with_hooks(
+        quote! {
+            // Build & execute the query.
+            let result = ::sqlx::query(#query)#filter_binds.execute(&#pool).await;
+
+            match result {
+                //= traits/spec.md#delete-trait
+                //# * In the case of a partial success, where the operation succeeded, but the item doesn't exist, the future MUST return `Ok(None)`.
+                Ok(result) if result.rows_affected() == 0 => Ok(None),
+
+                //= traits/spec.md#delete-trait
+                //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
+                Ok(_) => Ok(Some(())),
+
+                //= traits/spec.md#delete-trait
+                //# * In the case of a failure, the future MUST return `Err()`.
+                Err(e) => Err(e),
+            }
+        },
+        &quote! {
+            <Self as ::storage_noodle_sql::macro_helpers::hooks::Hooks>::before_delete(&*id)
+        },
+        &quote! {
+            if result.is_some() {
+                <Self as ::storage_noodle_sql::macro_helpers::hooks::Hooks>::after_delete(&*id);
+            }
+        },
+    );
+
     // Implement the trait.
     quote! {
-        impl #impl_generics ::storage_noodle_sql::macro_helpers::Delete<::storage_noodle_sql::SqlBacking<#backing_db, #raw_id>> for #ident #type_generics #where_clause
+        impl #impl_generics ::storage_noodle_sql::macro_helpers::Delete<#storage> for #ident #type_generics #where_clause
         {
-            type Error = ::sqlx::Error;
+            type Error = #error;
 
             fn delete(
-                storage: impl ::core::ops::Deref<Target = ::storage_noodle_sql::SqlBacking<#backing_db, #raw_id>>
+                storage: impl ::core::ops::Deref<Target = #storage>
                 + ::core::marker::Send,
                 id: impl ::core::ops::Deref<Target = ::storage_noodle_sql::macro_helpers::AssocId<Self, #raw_id>> + ::core::marker::Send
             ) -> impl Future<Output = ::core::result::Result<::core::option::Option<()>, Self::Error>> + ::core::marker::Send {
                 async move {
-                    // Build & execute the query.
-                    let result = ::sqlx::query(#query).bind(id.as_raw()).execute(&storage.pool).await;
-
-                    match result {
-                        //= traits/spec.md#delete-trait
-                        //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
-                        Ok(row) => Ok(Some(())),
-
-                        //= traits/spec.md#delete-trait
-                        //# * In the case of a partial success, where the operation succeeded, but the item doesn't exist, the future MUST return `Ok(None)`.
-                        Err(::sqlx::Error::RowNotFound) => Ok(None),
-
-                        //= traits/spec.md#delete-trait
-                        //# * In the case of a failure, the future MUST return `Err()`.
-                        Err(e) => Err(e),
-                    }
+                    #body
                 }
             }
         }
@@ -0,0 +1,170 @@
+use crate::attr::for_each_attr;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Implementation of [`crate::CreateWithTtl`].
+pub fn create_with_ttl(item: &syn::ItemStruct) -> TokenStream {
+    for_each_attr(item, create_with_ttl_impl)
+}
+
+/// Implementation of [`crate::PurgeExpired`].
+pub fn purge_expired(item: &syn::ItemStruct) -> TokenStream {
+    for_each_attr(item, purge_expired_impl)
+}
+
+/// Builds the [`crate::sql::Table`] for an expiring struct. Returns a compile error if the struct
+/// doesn't expire.
+fn expiring_table(
+    item: &syn::ItemStruct,
+    backing_db: &syn::Type,
+    raw_id: &syn::Type,
+    derive: &str,
+) -> Result<crate::sql::Table, TokenStream> {
+    let options = crate::attr::Options::from_item(item).map_err(|e| e.to_compile_error())?;
+
+    if !options.expires {
+        return Err(syn::Error::new_spanned(
+            &item.ident,
+            format!("`{derive}` requires `#[storage_noodle(expires)]` on this struct"),
+        )
+        .to_compile_error());
+    }
+
+    crate::sql::Table::new(item, &options, backing_db, raw_id).map_err(|e| e.to_compile_error())
+}
+
+/// Per-attribute implementation for [`create_with_ttl`].
+fn create_with_ttl_impl(
+    item: &syn::ItemStruct,
+    backing_db: &syn::Type,
+    raw_id: &syn::Type,
+) -> TokenStream {
+    let syn::ItemStruct { ident, .. } = item.clone();
+
+    // Split generics.
+    let (impl_generics, type_generics, where_clause) =
+        match crate::attr::split_generics_with_raw_id_attr(item, raw_id) {
+            Ok(v) => v,
+            Err(e) => return e.to_compile_error(),
+        };
+
+    // The table description.
+    let table = match expiring_table(item, backing_db, raw_id, "CreateWithTtl") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let storage = &table.storage;
+
+    // List of columns to insert, including the expiry timestamp.
+    let mut insert_columns = table.create_columns();
+    insert_columns.push(table.options.column_name(crate::sql::EXPIRES_AT_FIELD_NAME));
+
+    // Build & execute the query, getting the raw id of the new item back.
+    let generate_id = table.generate_id();
+    let mut bind_calls = table.create_binds();
+    bind_calls.extend(quote! {.bind(::storage_noodle_sql::macro_helpers::expires_at(ttl))});
+    let insert = table.insert_returning_id(&insert_columns, &bind_calls);
+
+    // The method body, wrapped in hook calls (if any).
+    let (error, body) = table.with_hooks(
+        quote! {
+            #generate_id
+
+            //= traits/spec.md#create-with-ttl-trait
+            //# * In the case of a failure, the future MUST return `Err()`.
+            //= traits/spec.md#create-with-ttl-trait
+            //# * In the case of a success, the future MUST return `Ok(AssocId<Self, RawId>)` - where the `AssocId` holds the Id of the newly created item.
+            #insert.map(::storage_noodle_sql::macro_helpers::AssocId::new)
+        },
+        &quote! {
+            ::storage_noodle_sql::macro_helpers::hooks::Hooks::before_create(self)
+        },
+        &quote! {
+            ::storage_noodle_sql::macro_helpers::hooks::Hooks::after_create(self, &result)
+        },
+    );
+
+    // Implement the trait.
+    quote! {
+        impl #impl_generics ::storage_noodle_sql::macro_helpers::CreateWithTtl<#storage> for #ident #type_generics #where_clause
+        {
+            type Error = #error;
+
+            fn create_with_ttl<'a>(
+                &'a self,
+                storage: impl ::core::ops::Deref<Target = #storage>
+                + ::core::marker::Send
+                + 'a,
+                ttl: ::core::time::Duration,
+            ) -> impl ::core::future::Future<Output = ::core::result::Result<::storage_noodle_sql::macro_helpers::AssocId<Self, #raw_id>, Self::Error>> + ::core::marker::Send {
+                async move {
+                    #body
+                }
+            }
+        }
+    }
+}
+
+/// Per-attribute implementation for [`purge_expired`].
+fn purge_expired_impl(
+    item: &syn::ItemStruct,
+    backing_db: &syn::Type,
+    raw_id: &syn::Type,
+) -> TokenStream {
+    let syn::ItemStruct { ident, .. } = item.clone();
+
+    // Split generics.
+    let (impl_generics, type_generics, where_clause) =
+        match crate::attr::split_generics_with_raw_id_attr(item, raw_id) {
+            Ok(v) => v,
+            Err(e) => return e.to_compile_error(),
+        };
+
+    // The table description.
+    let table = match expiring_table(item, backing_db, raw_id, "PurgeExpired") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let storage = &table.storage;
+    let pool = &table.pool;
+
+    // The SQL query to run.
+    let query = {
+        let query = format!(
+            "
+                DELETE FROM {}
+                WHERE {};
+            ",
+            table.quoted_name(),
+            table.expired_filter(),
+        );
+        table.query(&query)
+    };
+
+    // List of `.bind()` calls that select the expired items.
+    let filter_binds = table.expired_filter_binds();
+
+    // Implement the trait.
+    quote! {
+        impl #impl_generics ::storage_noodle_sql::macro_helpers::PurgeExpired<#storage> for #ident #type_generics #where_clause
+        {
+            type Error = ::sqlx::Error;
+
+            fn purge_expired(
+                storage: impl ::core::ops::Deref<Target = #storage>
+                + ::core::marker::Send,
+            ) -> impl Future<Output = ::core::result::Result<u64, Self::Error>> + ::core::marker::Send {
+                async move {
+                    // Build & execute the query.
+                    //= traits/spec.md#purge-expired-trait
+                    //# * In the case of a failure, the future MUST return `Err()`.
+                    let result = ::sqlx::query(#query)#filter_binds.execute(&#pool).await?;
+
+                    //= traits/spec.md#purge-expired-trait
+                    //# * In the case of a success, the future MUST return `Ok(u64)` - where the `u64` is the number of items that were removed.
+                    Ok(result.rows_affected())
+                }
+            }
+        }
+    }
+}
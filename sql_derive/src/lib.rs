@@ -10,38 +10,132 @@ mod attr;
 /// Derives for `Create`, `Read`, `Update`, and `Delete` traits.
 mod crud;
 
+/// Derives for `CreateWithTtl` and `PurgeExpired` traits.
+mod expiry;
+
+/// Derive for `List` trait.
+mod list;
+
 /// Derive for `SqlTable`.
 mod schema;
 
+/// Derives for `Restore` and `Purge` traits.
+mod soft_delete;
+
 /// SQL-related utils.
 mod sql;
 
+/// Derive for `Storable`.
+mod storable;
+
+/// Derive for `CreateWithId` trait.
+mod with_id;
+
 /// Derives `SqlTable` for a type
-#[proc_macro_derive(SqlTable, attributes(storage_noodle_raw_id))]
+#[proc_macro_derive(SqlTable, attributes(storage_noodle_raw_id, storage_noodle))]
 pub fn sql_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     schema::sql_table(&syn::parse_macro_input!(input)).into()
 }
 
 /// Derives `Create` for a type
-#[proc_macro_derive(Create, attributes(storage_noodle_sql, storage_noodle_raw_id))]
+#[proc_macro_derive(
+    Create,
+    attributes(storage_noodle_sql, storage_noodle_raw_id, storage_noodle)
+)]
 pub fn create(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     crud::create(&syn::parse_macro_input!(input)).into()
 }
 
 /// Derives `Read` for a type
-#[proc_macro_derive(Read, attributes(storage_noodle_sql, storage_noodle_raw_id))]
+#[proc_macro_derive(
+    Read,
+    attributes(storage_noodle_sql, storage_noodle_raw_id, storage_noodle)
+)]
 pub fn read(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     crud::read(&syn::parse_macro_input!(input)).into()
 }
 
 /// Derives `Update` for a type
-#[proc_macro_derive(Update, attributes(storage_noodle_sql, storage_noodle_raw_id))]
+#[proc_macro_derive(
+    Update,
+    attributes(storage_noodle_sql, storage_noodle_raw_id, storage_noodle)
+)]
 pub fn update(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     crud::update(&syn::parse_macro_input!(input)).into()
 }
 
 /// Derives `Delete` for a type
-#[proc_macro_derive(Delete, attributes(storage_noodle_sql, storage_noodle_raw_id))]
+#[proc_macro_derive(
+    Delete,
+    attributes(storage_noodle_sql, storage_noodle_raw_id, storage_noodle)
+)]
 pub fn delete(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     crud::delete(&syn::parse_macro_input!(input)).into()
 }
+
+/// Derives `Restore` for a type
+#[proc_macro_derive(
+    Restore,
+    attributes(storage_noodle_sql, storage_noodle_raw_id, storage_noodle)
+)]
+pub fn restore(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    soft_delete::restore(&syn::parse_macro_input!(input)).into()
+}
+
+/// Derives `Purge` for a type
+#[proc_macro_derive(
+    Purge,
+    attributes(storage_noodle_sql, storage_noodle_raw_id, storage_noodle)
+)]
+pub fn purge(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    soft_delete::purge(&syn::parse_macro_input!(input)).into()
+}
+
+/// Derives `CreateWithTtl` for a type
+#[proc_macro_derive(
+    CreateWithTtl,
+    attributes(storage_noodle_sql, storage_noodle_raw_id, storage_noodle)
+)]
+pub fn create_with_ttl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expiry::create_with_ttl(&syn::parse_macro_input!(input)).into()
+}
+
+/// Derives `PurgeExpired` for a type
+#[proc_macro_derive(
+    PurgeExpired,
+    attributes(storage_noodle_sql, storage_noodle_raw_id, storage_noodle)
+)]
+pub fn purge_expired(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expiry::purge_expired(&syn::parse_macro_input!(input)).into()
+}
+
+/// Derives `CreateWithId` for a type
+#[proc_macro_derive(
+    CreateWithId,
+    attributes(storage_noodle_sql, storage_noodle_raw_id, storage_noodle)
+)]
+pub fn create_with_id(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    with_id::create_with_id(&syn::parse_macro_input!(input)).into()
+}
+
+/// Derives `List` for a type
+#[proc_macro_derive(
+    List,
+    attributes(storage_noodle_sql, storage_noodle_raw_id, storage_noodle)
+)]
+pub fn list(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    list::list(&syn::parse_macro_input!(input)).into()
+}
+
+/// Derives `SqlTable`, `sqlx::FromRow`, and the `Create`, `Read`, `Update`, `Delete`, and `List`
+/// traits for a type.
+///
+/// `#[storable(only(create, read))]` only derives the listed traits (and `SqlTable` and
+/// `sqlx::FromRow`).
+#[proc_macro_derive(
+    Storable,
+    attributes(storage_noodle_sql, storage_noodle_raw_id, storage_noodle, storable)
+)]
+pub fn storable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    storable::storable(&syn::parse_macro_input!(input)).into()
+}
@@ -0,0 +1,98 @@
+use crate::attr::for_each_attr;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Implementation of [`crate::List`].
+pub fn list(item: &syn::ItemStruct) -> TokenStream {
+    for_each_attr(item, list_impl)
+}
+
+/// Per-attribute implementation for [`list`].
+fn list_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Type) -> TokenStream {
+    let syn::ItemStruct { ident, .. } = item.clone();
+
+    // Split generics.
+    let (impl_generics, type_generics, where_clause) =
+        match crate::attr::split_generics_with_raw_id_attr(item, raw_id) {
+            Ok(v) => v,
+            Err(e) => return e.to_compile_error(),
+        };
+
+    // The table description.
+    let table = match crate::attr::Options::from_item(item)
+        .and_then(|options| crate::sql::Table::new(item, &options, backing_db, raw_id))
+    {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
+    };
+    let storage = &table.storage;
+    let pool = &table.pool;
+
+    // The SQL query to run.
+    let query = {
+        let mut columns: Vec<_> = table.columns.iter().map(|c| c.name.clone()).collect();
+        if table.key_columns().is_empty() {
+            columns.push(table.options.id_column_name());
+        }
+
+        let query = format!(
+            "
+                SELECT {} FROM {}
+                WHERE {}
+                ORDER BY {};
+            ",
+            table.column_list(&columns), // List of column names (in order).
+            table.quoted_name(),
+            table.list_filter(),
+            table.column_list(&table.id_columns()),
+        );
+        table.query(&query)
+    };
+
+    // List of `.bind()` calls that select the items.
+    let filter_binds = table.list_filter_binds();
+
+    // Statement that gets the raw id of an `item` from a `row`: from the item's `primary_key`
+    // fields, or the id column (the last one). Postgres folds unquoted names to lower case, so it
+    // isn't read by name.
+    let get_raw = table.key_value(&quote! {item}).map_or_else(
+        || {
+            let id_index = table.columns.len();
+            quote! {let raw: #raw_id = ::sqlx::Row::try_get(row, #id_index)?;}
+        },
+        |key| quote! {let raw: #raw_id = #key;},
+    );
+
+    // Implement the trait.
+    quote! {
+        impl #impl_generics ::storage_noodle_sql::macro_helpers::List<#storage> for #ident #type_generics #where_clause
+        {
+            type Error = ::sqlx::Error;
+
+            fn list(
+                storage: impl ::core::ops::Deref<Target = #storage>
+                + ::core::marker::Send,
+            ) -> impl ::core::future::Future<Output = ::core::result::Result<::storage_noodle_sql::macro_helpers::Items<Self, #raw_id>, Self::Error>> + ::core::marker::Send {
+                async move {
+                    // Build the query.
+                    let query = ::sqlx::query(#query)#filter_binds;
+
+                    // Get the rows back from the query.
+                    //= traits/spec.md#list-trait
+                    //# * In the case of a failure, the future MUST return `Err()`.
+                    let rows = query.fetch_all(&#pool).await?;
+
+                    //= traits/spec.md#list-trait
+                    //# * In the case of a success, the future MUST return `Ok(Vec<(AssocId<Self, RawId>, Self)>)` - where the `Vec` holds every item that the [`Read`](#read-trait) trait would return, along with its Id.
+                    rows.iter()
+                        .map(|row| {
+                            let item = <Self as ::sqlx::FromRow<<#backing_db as ::sqlx::Database>::Row>>::from_row(row)?;
+                            #get_raw
+                            Ok((::storage_noodle_sql::macro_helpers::AssocId::new(raw), item))
+                        })
+                        .collect()
+                }
+            }
+        }
+    }
+}
@@ -24,6 +24,12 @@ pub fn sql_table_impl(
         None => None,
     };
 
+    // Get the struct-level options.
+    let options = match crate::attr::Options::from_item(item) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
+    };
+
     // Split generics.
     let (impl_generics, type_generics, where_clause) =
         match crate::attr::split_generics_with_raw_id_attr(item, raw_id) {
@@ -31,65 +37,317 @@ pub fn sql_table_impl(
             Err(e) => return e.to_compile_error(),
         };
 
-    // List of sql rows to put in the vec.
-    let sql_rows = fields
+    // The column names.
+    let columns = match crate::sql::Column::from_fields(&fields, &options) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    // The conditions on several columns.
+    let checks = &options.checks;
+
+    // The indexes on single fields, then on several fields.
+    let indexes = match indexes(&options, &columns, &options.table_name(item)) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    // Columns that aren't struct fields (the id is a struct field if there are `primary_key`
+    // fields).
+    let key_column = columns.iter().find(|column| column.options.primary_key);
+    let extra_columns = extra_columns(&options, backing_db, raw_id, key_column.is_none());
+
+    // List of sql rows to put in the vec (skipped fields have no column), then the extra ones.
+    let sql_rows = columns
         .iter()
-        .enumerate()
-        .map(|(index, syn::Field { ident, ty, .. })| {
-            // Fall back to index if there is no field name (unit structs).
-            let name = syn::LitStr::new(
-                &ident
-                    .clone().map_or_else(|| index.to_string(), |id| id.to_string()),
-                proc_macro2::Span::call_site(),
-            );
-
-            // Replace the id generic (if it exists) with the raw id concrete type.
-            let processed_ty = match &raw_id_generic {
-                Some(raw_id_generic) => crate::attr::make_concrete(ty, raw_id_generic, raw_id),
-                None => ty.clone(),
-            };
+        .map(|column| sql_column(column, raw_id_generic.as_ref(), backing_db, raw_id))
+        .chain(extra_columns);
 
-            quote! {
-                ::storage_noodle_sql::schema::SqlColumn {
+    // The table name, the name of its (first) id column, and the schema it is in (if any).
+    let name = syn::LitStr::new(&options.table_name(item), proc_macro2::Span::call_site());
+    let id_name = syn::LitStr::new(
+        &key_column.map_or_else(|| options.id_column_name(), |column| column.name.clone()),
+        proc_macro2::Span::call_site(),
+    );
+    let schema = optional_string(options.schema.as_ref());
+
+    // Implement the trait.
+    quote! {
+        impl #impl_generics ::storage_noodle_sql::schema::MakeSqlTable<#backing_db> for #ident #type_generics #where_clause {
+            fn table() -> ::storage_noodle_sql::schema::SqlTable {
+                let columns = ::std::vec![#(#sql_rows),*];
+
+                ::storage_noodle_sql::schema::SqlTable {
                     name: #name.to_string(),
-                    ty: ::sqlx::TypeInfo::name(&<#processed_ty as ::sqlx::Type<#backing_db>>::type_info()).to_string(),
-                    column_type: ::storage_noodle_sql::schema::ColumnType::Data,
+                    schema: #schema,
+                    columns,
+                    indexes: ::std::vec![#(#indexes),*],
+                    checks: ::std::vec![#(#checks.to_string()),*],
                 }
             }
-        });
-
-    let sql_rows_punctuated =
-        itertools::Itertools::intersperse(sql_rows, quote! {,}).collect::<TokenStream>();
-
-    // Extra id (primary key) column.
-    let id_column = {
-        let name = syn::LitStr::new(crate::sql::ID_FIELD_NAME, proc_macro2::Span::call_site());
-        quote! {
-            ::storage_noodle_sql::schema::SqlColumn {
-                name: #name.to_string(),
-                ty: ::sqlx::TypeInfo::name(&<#raw_id as ::sqlx::Type<#backing_db>>::type_info()).to_string(),
-                column_type: ::storage_noodle_sql::schema::ColumnType::PrimaryKey,
+
+            fn table_name() -> ::std::string::String {
+                #name.to_string()
+            }
+
+            fn table_schema() -> ::core::option::Option<::std::string::String> {
+                #schema
+            }
+
+            fn id_column_name() -> ::std::string::String {
+                #id_name.to_string()
             }
         }
+    }
+}
+
+/// The `SqlColumn` of a struct field.
+fn sql_column(
+    column: &crate::sql::Column,
+    raw_id_generic: Option<&syn::Ident>,
+    backing_db: &syn::Type,
+    raw_id: &syn::Type,
+) -> TokenStream {
+    let ty = &column.ty;
+    let field_options = &column.options;
+    let name = syn::LitStr::new(&column.name, proc_macro2::Span::call_site());
+    let column_type = if field_options.primary_key {
+        quote! {PrimaryKey(::storage_noodle_sql::schema::IdGeneration::Client)}
+    } else {
+        quote! {Data}
     };
 
-    // The table name.
-    let name = syn::LitStr::new(&ident.to_string(), proc_macro2::Span::call_site());
+    // Replace the id generic (if it exists) with the raw id concrete type.
+    let processed_ty = match raw_id_generic {
+        Some(raw_id_generic) => crate::attr::make_concrete(ty, raw_id_generic, raw_id),
+        None => ty.clone(),
+    };
+
+    // Nullable if the field is an `Option` (unless it is overridden).
+    let nullable = field_options
+        .nullable
+        .unwrap_or_else(|| crate::attr::is_option(ty));
+
+    // Foreign key to the referenced table (if the field is an `AssocId`).
+    let references = foreign_key(&processed_ty, field_options, nullable, backing_db);
+
+    // The database default and check constraint (if any).
+    let default = optional_string(field_options.default.as_ref());
+    let check = optional_string(field_options.check.as_ref());
 
-    // Implement the trait.
     quote! {
-        impl #impl_generics ::storage_noodle_sql::schema::MakeSqlTable<#backing_db> for #ident #type_generics #where_clause {
-            fn table() -> ::storage_noodle_sql::schema::SqlTable {
-                let columns = ::std::vec![
-                    #sql_rows_punctuated,
-                    #id_column
-                ];
+        ::storage_noodle_sql::schema::SqlColumn {
+            name: #name.to_string(),
+            ty: ::sqlx::TypeInfo::name(&<#processed_ty as ::sqlx::Type<#backing_db>>::type_info()).to_string(),
+            column_type: ::storage_noodle_sql::schema::ColumnType::#column_type,
+            references: #references,
+            default: #default,
+            nullable: #nullable,
+            check: #check,
+        }
+    }
+}
 
-                ::storage_noodle_sql::schema::SqlTable {
+/// An `Option<String>` expression holding `value` (if any).
+fn optional_string(value: Option<&String>) -> TokenStream {
+    value.map_or_else(
+        || quote! {::core::option::Option::None},
+        |value| quote! {::core::option::Option::Some(#value.to_string())},
+    )
+}
+
+/// The `SqlIndex`es of a table: an index for each `index` or `unique` field, then the struct-level
+/// indexes. Indexes are named after the table and their columns, and unique indexes of tenant
+/// scoped structs include the tenant id column.
+fn indexes(
+    options: &crate::attr::Options,
+    columns: &[crate::sql::Column],
+    table_name: &str,
+) -> syn::Result<Vec<TokenStream>> {
+    let single = columns.iter().filter_map(|column| {
+        column
+            .options
+            .index
+            .map(|unique| (vec![column.name.clone()], unique))
+    });
+
+    let mut indexes: Vec<_> = single.collect();
+    for index in &options.indexes {
+        let names = index
+            .fields
+            .iter()
+            .map(|field| {
+                columns
+                    .iter()
+                    .find(|column| column.ident == *field)
+                    .map(|column| column.name.clone())
+                    .ok_or_else(|| syn::Error::new_spanned(field, "not a stored field"))
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        indexes.push((names, index.unique));
+    }
+
+    // Unique values only need to be unique within a tenant, so unique indexes start with the
+    // tenant id column of tenant scoped structs.
+    if options.tenant.is_some() {
+        let tenant = options.column_name(crate::sql::TENANT_FIELD_NAME);
+        for (names, _) in indexes.iter_mut().filter(|(_, unique)| *unique) {
+            names.insert(0, tenant.clone());
+        }
+    }
+
+    Ok(indexes
+        .into_iter()
+        .map(|(names, unique)| {
+            let suffix = if unique { "key" } else { "idx" };
+            let name = format!("{table_name}_{}_{suffix}", names.join("_"));
+            quote! {
+                ::storage_noodle_sql::schema::SqlIndex {
                     name: #name.to_string(),
-                    columns,
+                    columns: ::std::vec![#(#names.to_string()),*],
+                    unique: #unique,
                 }
             }
+        })
+        .collect())
+}
+
+/// The `references` of a data column: a foreign key if the field is an `AssocId` or `Ref`, and
+/// [`None`] otherwise.
+fn foreign_key(
+    ty: &syn::Type,
+    field_options: &crate::attr::FieldOptions,
+    nullable: bool,
+    backing_db: &syn::Type,
+) -> TokenStream {
+    match crate::attr::referenced_type(ty) {
+        Some(referenced) if !field_options.no_foreign_key => {
+            let on_delete = field_options
+                .on_delete
+                .clone()
+                .unwrap_or_else(|| syn::Ident::new("NoAction", proc_macro2::Span::call_site()));
+            if on_delete == "SetNull" && !nullable {
+                return syn::Error::new(
+                    on_delete.span(),
+                    "`on_delete = set_null` can only be used on nullable (`Option`) fields",
+                )
+                .to_compile_error();
+            }
+            quote! {
+                ::core::option::Option::Some(::storage_noodle_sql::schema::ForeignKey {
+                    schema: <#referenced as ::storage_noodle_sql::schema::MakeSqlTable<#backing_db>>::table_schema(),
+                    table: <#referenced as ::storage_noodle_sql::schema::MakeSqlTable<#backing_db>>::table_name(),
+                    column: <#referenced as ::storage_noodle_sql::schema::MakeSqlTable<#backing_db>>::id_column_name(),
+                    on_delete: ::storage_noodle_sql::schema::OnDelete::#on_delete,
+                })
+            }
+        }
+        None if field_options.on_delete.is_some() => syn::Error::new_spanned(
+            ty,
+            "`on_delete` can only be used on `AssocId` or `Ref` fields",
+        )
+        .to_compile_error(),
+        _ => quote! {::core::option::Option::None},
+    }
+}
+
+/// The columns that aren't struct fields (in order): the tenant id, soft deletion timestamp, and
+/// expiry timestamp columns (if the struct has them), and the id column (if `id_column`).
+fn extra_columns(
+    options: &crate::attr::Options,
+    backing_db: &syn::Type,
+    raw_id: &syn::Type,
+    id_column: bool,
+) -> Vec<TokenStream> {
+    // Extra id (primary key) column, which is generated by the id generator or the `id_default`
+    // expression (if the struct has one), and auto-incremented otherwise.
+    let generation = if options.id_generator.is_some() {
+        quote! {Client}
+    } else if options.id_default.is_some() {
+        quote! {Default}
+    } else {
+        quote! {AutoIncrement}
+    };
+    let id_column = id_column.then(|| {
+        extra_column(
+            &options.id_column_name(),
+            raw_id,
+            backing_db,
+            &quote! {PrimaryKey(::storage_noodle_sql::schema::IdGeneration::#generation)},
+            false,
+            options.id_default.as_ref(),
+        )
+    });
+
+    // Extra tenant id column (if the struct is tenant scoped).
+    let tenant_column = options.tenant.as_ref().map(|tenant| {
+        extra_column(
+            &options.column_name(crate::sql::TENANT_FIELD_NAME),
+            tenant,
+            backing_db,
+            &quote! {Data},
+            false,
+            None,
+        )
+    });
+
+    // Extra soft deletion timestamp column (if the struct is soft deleted).
+    let timestamp: syn::Type = syn::parse_quote! {i64};
+    let deleted_at_column = options.soft_delete.then(|| {
+        extra_column(
+            &options.column_name(crate::sql::DELETED_AT_FIELD_NAME),
+            &timestamp,
+            backing_db,
+            &quote! {Data},
+            true,
+            None,
+        )
+    });
+
+    // Extra expiry timestamp column (if the struct expires).
+    let expires_at_column = options.expires.then(|| {
+        extra_column(
+            &options.column_name(crate::sql::EXPIRES_AT_FIELD_NAME),
+            &timestamp,
+            backing_db,
+            &quote! {Data},
+            true,
+            None,
+        )
+    });
+
+    [
+        tenant_column,
+        deleted_at_column,
+        expires_at_column,
+        id_column,
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// A column that isn't a struct field (e.g. the id), with the `ColumnType` variant `column_type`.
+/// The column is nullable if `nullable`, and defaults to `default` (if any).
+fn extra_column(
+    name: &str,
+    ty: &syn::Type,
+    backing_db: &syn::Type,
+    column_type: &TokenStream,
+    nullable: bool,
+    default: Option<&String>,
+) -> TokenStream {
+    let name = syn::LitStr::new(name, proc_macro2::Span::call_site());
+    let default = optional_string(default);
+    quote! {
+        ::storage_noodle_sql::schema::SqlColumn {
+            name: #name.to_string(),
+            ty: ::sqlx::TypeInfo::name(&<#ty as ::sqlx::Type<#backing_db>>::type_info()).to_string(),
+            column_type: ::storage_noodle_sql::schema::ColumnType::#column_type,
+            references: ::core::option::Option::None,
+            default: #default,
+            nullable: #nullable,
+            check: ::core::option::Option::None,
         }
     }
 }
@@ -0,0 +1,170 @@
+use crate::attr::for_each_attr;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Implementation of [`crate::Restore`].
+pub fn restore(item: &syn::ItemStruct) -> TokenStream {
+    for_each_attr(item, restore_impl)
+}
+
+/// Implementation of [`crate::Purge`].
+pub fn purge(item: &syn::ItemStruct) -> TokenStream {
+    for_each_attr(item, purge_impl)
+}
+
+/// Builds the [`crate::sql::Table`] for a soft deleted struct. Returns a compile error if the
+/// struct isn't soft deleted.
+fn soft_delete_table(
+    item: &syn::ItemStruct,
+    backing_db: &syn::Type,
+    raw_id: &syn::Type,
+    derive: &str,
+) -> Result<crate::sql::Table, TokenStream> {
+    let options = crate::attr::Options::from_item(item).map_err(|e| e.to_compile_error())?;
+
+    if !options.soft_delete {
+        return Err(syn::Error::new_spanned(
+            &item.ident,
+            format!("`{derive}` requires `#[storage_noodle(soft_delete)]` on this struct"),
+        )
+        .to_compile_error());
+    }
+
+    crate::sql::Table::new(item, &options, backing_db, raw_id).map_err(|e| e.to_compile_error())
+}
+
+/// Per-attribute implementation for [`restore`].
+fn restore_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Type) -> TokenStream {
+    let syn::ItemStruct { ident, .. } = item.clone();
+
+    // Split generics.
+    let (impl_generics, type_generics, where_clause) =
+        match crate::attr::split_generics_with_raw_id_attr(item, raw_id) {
+            Ok(v) => v,
+            Err(e) => return e.to_compile_error(),
+        };
+
+    // The table description.
+    let table = match soft_delete_table(item, backing_db, raw_id, "Restore") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let storage = &table.storage;
+    let pool = &table.pool;
+
+    // The SQL query to run.
+    let query = {
+        let query = format!(
+            "
+                UPDATE {}
+                SET {}=NULL
+                WHERE {};
+            ",
+            table.quoted_name(),
+            table.column(crate::sql::DELETED_AT_FIELD_NAME),
+            table.deleted_filter(),
+        );
+        table.query(&query)
+    };
+
+    // List of `.bind()` calls that select the item.
+    let filter_binds = table.deleted_filter_binds();
+
+    // Implement the trait.
+    quote! {
+        impl #impl_generics ::storage_noodle_sql::macro_helpers::Restore<#storage> for #ident #type_generics #where_clause
+        {
+            type Error = ::sqlx::Error;
+
+            fn restore(
+                storage: impl ::core::ops::Deref<Target = #storage>
+                + ::core::marker::Send,
+                id: impl ::core::ops::Deref<Target = ::storage_noodle_sql::macro_helpers::AssocId<Self, #raw_id>> + ::core::marker::Send
+            ) -> impl Future<Output = ::core::result::Result<::core::option::Option<()>, Self::Error>> + ::core::marker::Send {
+                async move {
+                    // Build & execute the query.
+                    //= traits/spec.md#restore-trait
+                    //# * In the case of a failure, the future MUST return `Err()`.
+                    let result = ::sqlx::query(#query)#filter_binds.execute(&#pool).await?;
+
+                    if result.rows_affected() == 0 {
+                        //= traits/spec.md#restore-trait
+                        //# * In the case of a partial success, where the operation succeeded, but there is no deleted item with the id, the future MUST return `Ok(None)`.
+                        Ok(None)
+                    } else {
+                        //= traits/spec.md#restore-trait
+                        //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
+                        Ok(Some(()))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Per-attribute implementation for [`purge`].
+fn purge_impl(item: &syn::ItemStruct, backing_db: &syn::Type, raw_id: &syn::Type) -> TokenStream {
+    let syn::ItemStruct { ident, .. } = item.clone();
+
+    // Split generics.
+    let (impl_generics, type_generics, where_clause) =
+        match crate::attr::split_generics_with_raw_id_attr(item, raw_id) {
+            Ok(v) => v,
+            Err(e) => return e.to_compile_error(),
+        };
+
+    // The table description.
+    let table = match soft_delete_table(item, backing_db, raw_id, "Purge") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let storage = &table.storage;
+    let pool = &table.pool;
+
+    // The SQL query to run.
+    let query = {
+        let query = format!(
+            "
+                DELETE FROM {}
+                WHERE {};
+            ",
+            table.quoted_name(),
+            table.deleted_filter(),
+        );
+        table.query(&query)
+    };
+
+    // List of `.bind()` calls that select the item.
+    let filter_binds = table.deleted_filter_binds();
+
+    // Implement the trait.
+    quote! {
+        impl #impl_generics ::storage_noodle_sql::macro_helpers::Purge<#storage> for #ident #type_generics #where_clause
+        {
+            type Error = ::sqlx::Error;
+
+            fn purge(
+                storage: impl ::core::ops::Deref<Target = #storage>
+                + ::core::marker::Send,
+                id: impl ::core::ops::Deref<Target = ::storage_noodle_sql::macro_helpers::AssocId<Self, #raw_id>> + ::core::marker::Send
+            ) -> impl Future<Output = ::core::result::Result<::core::option::Option<()>, Self::Error>> + ::core::marker::Send {
+                async move {
+                    // Build & execute the query.
+                    //= traits/spec.md#purge-trait
+                    //# * In the case of a failure, the future MUST return `Err()`.
+                    let result = ::sqlx::query(#query)#filter_binds.execute(&#pool).await?;
+
+                    if result.rows_affected() == 0 {
+                        //= traits/spec.md#purge-trait
+                        //# * In the case of a partial success, where the operation succeeded, but there is no deleted item with the id, the future MUST return `Ok(None)`.
+                        Ok(None)
+                    } else {
+                        //= traits/spec.md#purge-trait
+                        //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
+                        Ok(Some(()))
+                    }
+                }
+            }
+        }
+    }
+}
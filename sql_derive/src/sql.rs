@@ -1,14 +1,95 @@
 //! # Semantics
 //!
-//! Table names are copied directly from the struct name.
+//! Table names are copied directly from the struct name, unless they are set with
+//! `#[storage_noodle(table = "name")]`. `#[storage_noodle(schema = "name")]` qualifies the table
+//! name with a schema (e.g. `bakery.cookies`).
 //!
-//! Column names are copied directly from the field name.
+//! Column names are copied directly from the field name, unless they are set with
+//! `#[storage_noodle(rename = "name")]` on the field. `#[storage_noodle(rename_all = "rule")]`
+//! renames every other column (including the extra columns below) with a rule such as
+//! `snake_case`. `Storable` decodes rows by column order, but structs that derive `sqlx::FromRow`
+//! themselves must rename their fields to match (e.g. with `#[sqlx(rename_all = "snake_case")]`).
 //!
-//! The Id field is defined by `ID_FIELD_NAME`.
+//! The Id field is defined by `ID_FIELD_NAME`, unless it is named with
+//! `#[storage_noodle(id_column = "name")]`. Fields marked with `#[storage_noodle(primary_key)]` are
+//! the id instead of a separate column: the raw id is the field's type, or a tuple of the fields'
+//! types (in order) if several fields make up a composite key. Updates don't change the key
+//! columns, and composite keys can't be referenced by a foreign key.
+//!
+//! Fields marked with `#[storage_noodle(skip)]` have no column, and are filled with
+//! `Default::default()` when read. Fields marked with `#[storage_noodle(read_only)]` are read, but
+//! never written by inserts or updates, so the database populates them (e.g. generated columns).
+//! `#[storage_noodle(default = "expr")]` gives the column a `DEFAULT expr` in the schema. Structs
+//! that derive `sqlx::FromRow` themselves must mark skipped fields with `#[sqlx(skip)]`. At least
+//! one field must have a column.
+//!
+//! Columns are `NOT NULL` unless their field is an `Option`, which can be overridden with
+//! `#[storage_noodle(nullable = true)]` or `#[storage_noodle(nullable = false)]`. The extra soft
+//! deletion and expiry timestamp columns are nullable.
+//!
+//! `#[storage_noodle(index)]` and `#[storage_noodle(unique)]` on a field give its column an index
+//! of its own (a field can't have both), and `#[storage_noodle(index(fields = [a, b], unique))]` on the struct indexes
+//! several fields. Indexes are named `{table}_{columns}_idx` (or `_key` if they are unique). Writes
+//! that conflict with a unique index fail with an error that `ConstraintError::is_conflict`
+//! recognises, and `CreateWithId` only returns `Ok(None)` if it is the id that conflicts. The
+//! unique indexes of tenant scoped structs start with the tenant id column, so that each tenant can
+//! use the same values.
+//!
+//! `#[storage_noodle(check = "condition")]` on a field gives its column a `CHECK` constraint, and on
+//! the struct adds a table-level `CHECK` constraint (which can use several columns). Writes that
+//! break one fail with an error whose `ConstraintError::constraint_violation` is a
+//! `ConstraintKind::Check`.
+//!
+//! Queries use `?` placeholders, which are numbered (`$1`, `$2`, ...) if the backing database is
+//! `Postgres`. The dialect is picked from the last segment of the backing database type in the
+//! `storage_noodle_sql` attribute, so it must be named by its `sqlx` name (e.g. `sqlx::Postgres`)
+//! rather than an alias.
+//!
+//! `MySql` has no `RETURNING`, so identifiers are quoted with backticks, and creates read the id
+//! back with `LAST_INSERT_ID()` (or use the client-side id, if the struct has an id generator).
+//!
+//! `AssocId<T, _>` and `Ref<T, _>` fields (optionally wrapped in an `Option`) reference the Id
+//! column of `T`'s table with a foreign key. `#[storage_noodle(on_delete = ACTION)]` on the field
+//! sets what happens when the referenced row is deleted (`no_action` by default, `restrict`,
+//! `cascade`, or `set_null`), and `#[storage_noodle(no_foreign_key)]` leaves the foreign key out.
+//! `set_null` needs a nullable column, so it is a compile error on a field that isn't an `Option`.
+//!
+//! Tenant scoped structs (`#[storage_noodle(tenant = TenantId)]`) get an extra column defined by
+//! `TENANT_FIELD_NAME`, which is set on insert and checked by every query.
+//!
+//! Soft deleted structs (`#[storage_noodle(soft_delete)]`) get an extra column defined by
+//! `DELETED_AT_FIELD_NAME`, which holds the unix time (in seconds) that the row was deleted at, or
+//! `NULL` if it hasn't been deleted.
+//!
+//! Expiring structs (`#[storage_noodle(expires)]`) get an extra column defined by
+//! `EXPIRES_AT_FIELD_NAME`, which holds the unix time (in seconds) that the row expires at, or
+//! `NULL` if it never expires.
+//!
+//! Structs with hooks (`#[storage_noodle(hooks)]`) run their `Hooks` around every create, update,
+//! and delete, and return a `HookError` instead of a [`sqlx::Error`].
+//!
+//! Structs with an id generator (`#[storage_noodle(id_generator = GENERATOR)]`) insert an id made
+//! by `GENERATOR` on create, instead of letting the database create it. Otherwise, the database
+//! creates ids with `#[storage_noodle(id_default = "expr")]` (e.g. `gen_random_uuid()`, which
+//! `MySql` can't return), or auto-increments them (an identity column on `Postgres`). On `Postgres`,
+//! `CreateWithId` moves the identity sequence past the ids it inserts, so that later creates don't
+//! generate an id that is already taken.
+
+use proc_macro2::TokenStream;
+use quote::quote;
 
 /// The standard name of the id field.
 pub const ID_FIELD_NAME: &str = "Id";
 
+/// The standard name of the tenant id field.
+pub const TENANT_FIELD_NAME: &str = "TenantId";
+
+/// The standard name of the soft deletion timestamp field.
+pub const DELETED_AT_FIELD_NAME: &str = "DeletedAt";
+
+/// The standard name of the expiry timestamp field.
+pub const EXPIRES_AT_FIELD_NAME: &str = "ExpiresAt";
+
 /// Describes a SQL column.
 pub struct Column {
     /// The column name.
@@ -16,25 +97,651 @@ pub struct Column {
 
     /// The struct field corrosponding to the column.
     pub ident: syn::Ident,
+
+    /// The type of the struct field.
+    pub ty: syn::Type,
+
+    /// The field-level options.
+    pub options: crate::attr::FieldOptions,
 }
 
 impl Column {
-    /// Creates a list of [`Column`]s from a [`syn::Fields`].
-    pub fn from_fields(value: &syn::Fields) -> Vec<Self> {
-        value
+    /// Whether the column is written by inserts and updates (it isn't `read_only`).
+    pub fn writable(&self) -> bool {
+        self.options.kind == crate::attr::FieldKind::ReadWrite
+    }
+
+    /// Creates a list of [`Column`]s from a [`syn::Fields`], naming them with the struct-level
+    /// options. Skipped fields have no column, and it is an error for every field to be skipped
+    /// (the inserts would have no columns).
+    pub fn from_fields(
+        value: &syn::Fields,
+        options: &crate::attr::Options,
+    ) -> syn::Result<Vec<Self>> {
+        let columns = value
             .iter()
             .enumerate()
-            .map(|(i, field)| {
+            .filter_map(|(i, field)| {
                 // Fall back to the index if the field has no name (unit structs).
                 let ident = field.ident.clone().unwrap_or_else(|| {
                     syn::Ident::new(&i.to_string(), proc_macro2::Span::mixed_site())
                 });
 
-                Self {
-                    name: ident.to_string(),
+                let field_options = match crate::attr::FieldOptions::from_field(field) {
+                    Ok(v) => v,
+                    Err(e) => return Some(Err(e)),
+                };
+                if field_options.kind == crate::attr::FieldKind::Skip {
+                    return None;
+                }
+                if field_options.primary_key
+                    && (options.id_generator.is_some()
+                        || options.id_column.is_some()
+                        || options.id_default.is_some())
+                {
+                    return Some(Err(syn::Error::new_spanned(
+                        field,
+                        "`primary_key` fields can't be used with `id_generator`, `id_column`, or \
+                         `id_default`",
+                    )));
+                }
+
+                let name = field_options
+                    .rename
+                    .clone()
+                    .unwrap_or_else(|| options.column_name(&ident.to_string()));
+
+                Some(Ok(Self {
+                    name,
                     ident,
+                    ty: field.ty.clone(),
+                    options: field_options,
+                }))
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        if columns.is_empty() {
+            return Err(syn::Error::new_spanned(
+                value,
+                "the struct needs at least one field that isn't skipped",
+            ));
+        }
+        Ok(columns)
+    }
+}
+
+/// The SQL dialect of a backing database.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// `?` placeholders (`SQLite`, and anything that isn't recognised).
+    Sqlite,
+
+    /// Numbered `$1` placeholders.
+    Postgres,
+
+    /// `?` placeholders, backtick quoted identifiers, and no `RETURNING`.
+    MySql,
+}
+
+impl Dialect {
+    /// Picks the dialect of a backing database type from its last path segment.
+    pub fn from_backing_db(backing_db: &syn::Type) -> Self {
+        let last = match backing_db {
+            syn::Type::Path(path) => path.path.segments.last(),
+            _ => None,
+        };
+
+        match last {
+            Some(segment) if segment.ident == "Postgres" => Self::Postgres,
+            Some(segment) if segment.ident == "MySql" => Self::MySql,
+            _ => Self::Sqlite,
+        }
+    }
+}
+
+/// Describes the table a struct is stored in, and the storage used to access it.
+pub struct Table {
+    /// The table name.
+    pub name: String,
+
+    /// The data columns.
+    pub columns: Vec<Column>,
+
+    /// The backing storage type that the traits are implemented for.
+    pub storage: TokenStream,
+
+    /// Expression that evaluates to the [`sqlx::Pool`], given a `storage` variable.
+    pub pool: TokenStream,
+
+    /// The raw id type.
+    pub raw_id: syn::Type,
+
+    /// The struct-level options.
+    pub options: crate::attr::Options,
+
+    /// The SQL dialect of the backing database.
+    pub dialect: Dialect,
+}
+
+impl Table {
+    /// Creates a [`Table`] from a struct and the arguments of one of it's `storage_noodle_sql`
+    /// attributes.
+    pub fn new(
+        item: &syn::ItemStruct,
+        options: &crate::attr::Options,
+        backing_db: &syn::Type,
+        raw_id: &syn::Type,
+    ) -> syn::Result<Self> {
+        let sql_backing = quote! {::storage_noodle_sql::SqlBacking<#backing_db, #raw_id>};
+
+        let (storage, pool) = match &options.tenant {
+            Some(tenant) => (
+                quote! {::storage_noodle_sql::macro_helpers::tenant::TenantScoped<#sql_backing, #tenant>},
+                quote! {storage.inner.pool},
+            ),
+            None => (sql_backing, quote! {storage.pool}),
+        };
+
+        // `LAST_INSERT_ID()` only reads back auto-incremented ids.
+        let dialect = Dialect::from_backing_db(backing_db);
+        if dialect == Dialect::MySql && options.id_default.is_some() {
+            return Err(syn::Error::new_spanned(
+                &item.ident,
+                "`id_default` can't be used with `MySql`, which can't return the created id",
+            ));
+        }
+
+        Ok(Self {
+            name: options.table_name(item),
+            columns: Column::from_fields(&item.fields, options)?,
+            storage,
+            pool,
+            raw_id: raw_id.clone(),
+            options: options.clone(),
+            dialect,
+        })
+    }
+
+    /// Turns a query with `?` placeholders into a string literal, numbering the placeholders if
+    /// the dialect needs it.
+    pub fn query(&self, query: &str) -> syn::LitStr {
+        let query = match self.dialect {
+            Dialect::Sqlite | Dialect::MySql => query.to_string(),
+            Dialect::Postgres => query
+                .split('?')
+                .enumerate()
+                .map(|(index, part)| {
+                    if index == 0 {
+                        part.to_string()
+                    } else {
+                        format!("${index}{part}")
+                    }
+                })
+                .collect(),
+        };
+        syn::LitStr::new(&query, proc_macro2::Span::mixed_site())
+    }
+
+    /// Quotes an identifier, if the dialect needs it.
+    pub fn quote(&self, name: &str) -> String {
+        match self.dialect {
+            Dialect::MySql => format!("`{name}`"),
+            Dialect::Sqlite | Dialect::Postgres => name.to_string(),
+        }
+    }
+
+    /// The (quoted) table name, qualified with the schema (if any).
+    pub fn quoted_name(&self) -> String {
+        match &self.options.schema {
+            Some(schema) => format!("{}.{}", self.quote(schema), self.quote(&self.name)),
+            None => self.quote(&self.name),
+        }
+    }
+
+    /// The (quoted) name of an extra column (e.g. [`ID_FIELD_NAME`]).
+    pub fn column(&self, name: &str) -> String {
+        self.quote(&self.options.column_name(name))
+    }
+
+    /// Quotes and joins a list of column names.
+    pub fn column_list(&self, names: &[String]) -> String {
+        names
+            .iter()
+            .map(|name| self.quote(name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Expression that inserts a row with the columns, and evaluates to a
+    /// `Result<RawId, sqlx::Error>` holding the id of the new row. `binds` are the `.bind()` calls
+    /// for the columns, and `raw` must hold the id (from [`Table::generate_id`]) if the struct has
+    /// an id generator.
+    pub fn insert_returning_id(&self, columns: &[String], binds: &TokenStream) -> TokenStream {
+        let pool = &self.pool;
+        let raw_id = &self.raw_id;
+
+        let insert = format!(
+            "
+                INSERT INTO {} ({})
+                VALUES ({})
+            ",
+            self.quoted_name(),
+            self.column_list(columns), // List of column names (in order).
+            vec!["?"; columns.len()].join(", "), // List of "?" - to be filled in with bind calls.
+        );
+
+        // The id of items with `primary_key` fields is already known.
+        if let Some(key) = self.key_value(&quote! {self}) {
+            let query = self.query(&format!("{insert};"));
+            return quote! {
+                ::sqlx::query(#query)#binds.execute(&#pool).await.map(|_| #key)
+            };
+        }
+
+        if self.dialect != Dialect::MySql {
+            let query = self.query(&format!(
+                "{insert}RETURNING {};",
+                self.quote(&self.options.id_column_name())
+            ));
+            return quote! {
+                ::sqlx::query_scalar(#query)#binds.fetch_one(&#pool).await
+            };
+        }
+
+        let query = self.query(&format!("{insert};"));
+        let execute = quote! {::sqlx::query(#query)#binds.execute(&#pool).await};
+        if self.options.id_generator.is_some() {
+            quote! {#execute.map(|_| raw)}
+        } else {
+            quote! {
+                #execute.and_then(|result| {
+                    <#raw_id as ::core::convert::TryFrom<u64>>::try_from(result.last_insert_id())
+                        .map_err(|e| ::sqlx::Error::Decode(::std::boxed::Box::new(e)))
+                })
+            }
+        }
+    }
+
+    /// The names of the columns that are written by an insert (in order).
+    pub fn insert_columns(&self) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .columns
+            .iter()
+            .filter(|c| c.writable())
+            .map(|c| c.name.clone())
+            .collect();
+        if self.options.tenant.is_some() {
+            names.push(self.options.column_name(TENANT_FIELD_NAME));
+        }
+        names
+    }
+
+    /// `.bind()` calls for the values written by an insert (in the same order as
+    /// [`Table::insert_columns`]).
+    pub fn insert_binds(&self) -> TokenStream {
+        let mut binds = self.data_binds();
+        if self.options.tenant.is_some() {
+            binds.extend(quote! {.bind(&storage.tenant)});
+        }
+        binds
+    }
+
+    /// The names of the columns that are written by a create (in order). Includes the id if it is
+    /// generated client-side.
+    pub fn create_columns(&self) -> Vec<String> {
+        let mut names = self.insert_columns();
+        if self.options.id_generator.is_some() {
+            names.push(self.options.id_column_name());
+        }
+        names
+    }
+
+    /// `.bind()` calls for the values written by a create (in the same order as
+    /// [`Table::create_columns`]), given the `raw` variable from [`Table::generate_id`].
+    pub fn create_binds(&self) -> TokenStream {
+        let mut binds = self.insert_binds();
+        if self.options.id_generator.is_some() {
+            binds.extend(quote! {.bind(&raw)});
+        }
+        binds
+    }
+
+    /// The names of the columns that are written by a create with a known id (in order).
+    pub fn insert_with_id_columns(&self) -> Vec<String> {
+        let mut names = self.insert_columns();
+        if self.key_columns().is_empty() {
+            names.push(self.options.id_column_name());
+        }
+        names
+    }
+
+    /// `.bind()` calls for the values written by a create with a known id (in the same order as
+    /// [`Table::insert_with_id_columns`]), given an `id` variable. Key columns are bound to the
+    /// id, rather than the item's fields.
+    pub fn insert_with_id_binds(&self) -> TokenStream {
+        let mut binds: TokenStream = self
+            .columns
+            .iter()
+            .filter(|column| column.writable())
+            .map(|column| {
+                let value = self.id_part(column).unwrap_or_else(|| {
+                    let field = &column.ident;
+                    quote! {&self.#field}
+                });
+                quote! {.bind(#value)}
+            })
+            .collect();
+        if self.options.tenant.is_some() {
+            binds.extend(quote! {.bind(&storage.tenant)});
+        }
+        if self.key_columns().is_empty() {
+            binds.extend(quote! {.bind(id.as_raw())});
+        }
+        binds
+    }
+
+    /// Expression that moves the identity sequence of a `Postgres` id column past an `id` that was
+    /// inserted explicitly, so that later creates don't generate it again. Evaluates to a
+    /// `Result<(), sqlx::Error>`, or is [`None`] if the database doesn't generate the ids.
+    ///
+    /// Only integer ids are identity columns (like in the generated schema), and tables without a
+    /// sequence are left alone, as `pg_get_serial_sequence` returns `NULL` for them.
+    pub fn advance_id_sequence(&self) -> Option<TokenStream> {
+        if self.dialect != Dialect::Postgres
+            || !self.key_columns().is_empty()
+            || self.options.id_generator.is_some()
+            || self.options.id_default.is_some()
+        {
+            return None;
+        }
+
+        // Unquoted names are lower cased, which `pg_get_serial_sequence` only does for the table.
+        let sequence = format!(
+            "pg_get_serial_sequence('{}', '{}')",
+            self.quoted_name(),
+            self.options.id_column_name().to_lowercase()
+        );
+        let query = self.query(&format!(
+            "SELECT setval({sequence}, GREATEST(?, pg_sequence_last_value({sequence}::regclass)));"
+        ));
+        let pool = &self.pool;
+        let raw_id = &self.raw_id;
+
+        Some(quote! {
+            match ::sqlx::TypeInfo::name(&<#raw_id as ::sqlx::Type<::sqlx::Postgres>>::type_info()) {
+                "INT2" | "INT4" | "INT8" => ::sqlx::query(#query)
+                    .bind(id.as_raw())
+                    .execute(&#pool)
+                    .await
+                    .map(|_| ()),
+                _ => Ok(()),
+            }
+        })
+    }
+
+    /// The columns that make up the primary key (empty if the id is a separate column).
+    pub fn key_columns(&self) -> Vec<&Column> {
+        self.columns
+            .iter()
+            .filter(|column| column.options.primary_key)
+            .collect()
+    }
+
+    /// The names of the columns that the id is stored in.
+    pub fn id_columns(&self) -> Vec<String> {
+        let keys = self.key_columns();
+        if keys.is_empty() {
+            vec![self.options.id_column_name()]
+        } else {
+            keys.iter().map(|column| column.name.clone()).collect()
+        }
+    }
+
+    /// Expression that evaluates to the part of an `id` variable's raw id that is stored in a key
+    /// column, or [`None`] if the column isn't part of the primary key.
+    fn id_part(&self, column: &Column) -> Option<TokenStream> {
+        let keys = self.key_columns();
+        let index = keys.iter().position(|key| key.ident == column.ident)?;
+        if keys.len() == 1 {
+            Some(quote! {id.as_raw()})
+        } else {
+            let index = syn::Index::from(index);
+            Some(quote! {&id.as_raw().#index})
+        }
+    }
+
+    /// Expression that evaluates to the raw id of `item`, cloned from its `primary_key` fields, or
+    /// [`None`] if the id is a separate column.
+    pub fn key_value(&self, item: &TokenStream) -> Option<TokenStream> {
+        let parts: Vec<_> = self
+            .key_columns()
+            .iter()
+            .map(|column| {
+                let field = &column.ident;
+                quote! {::core::clone::Clone::clone(&#item.#field)}
+            })
+            .collect();
+
+        match parts.as_slice() {
+            [] => None,
+            [part] => Some(part.clone()),
+            parts => Some(quote! {(#(#parts),*)}),
+        }
+    }
+
+    /// The columns that are written by an update (every writable column that isn't part of the
+    /// primary key).
+    pub fn value_columns(&self) -> Vec<&Column> {
+        self.columns
+            .iter()
+            .filter(|column| column.writable() && !column.options.primary_key)
+            .collect()
+    }
+
+    /// `.bind()` calls for [`Table::value_columns`] (in order).
+    pub fn value_binds(&self) -> TokenStream {
+        self.value_columns()
+            .iter()
+            .map(|column| {
+                let field = &column.ident;
+                quote! {.bind(&self.#field)}
+            })
+            .collect()
+    }
+
+    /// Statement that generates a client-side id into a `raw` variable (if the struct has an id
+    /// generator).
+    pub fn generate_id(&self) -> TokenStream {
+        let raw_id = &self.raw_id;
+        self.options
+            .id_generator
+            .as_ref()
+            .map(|generator| {
+                quote! {
+                    let raw = ::storage_noodle_sql::macro_helpers::id::IdGenerator::<#raw_id>::generate(&#generator);
                 }
             })
+            .unwrap_or_default()
+    }
+
+    /// `.bind()` calls for the writable data columns (in order).
+    pub fn data_binds(&self) -> TokenStream {
+        self.columns
+            .iter()
+            .filter(|column| column.writable())
+            .map(|column| {
+                let field = &column.ident;
+                quote! {.bind(&self.#field)}
+            })
             .collect()
     }
+
+    /// The conditions that identify a single row by id.
+    fn key_conditions(&self) -> Vec<String> {
+        let mut conditions = vec![self.id_filter()];
+        if self.options.tenant.is_some() {
+            conditions.push(format!("{}=?", self.column(TENANT_FIELD_NAME)));
+        }
+        conditions
+    }
+
+    /// The `WHERE` condition that selects a row by id alone (whatever its tenant, and whether it is
+    /// visible or not).
+    pub fn id_filter(&self) -> String {
+        self.id_columns()
+            .iter()
+            .map(|name| format!("{}=?", self.quote(name)))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+
+    /// `.bind()` calls for [`Table::id_filter`], given an `id` variable.
+    pub fn id_binds(&self) -> TokenStream {
+        if self.key_columns().len() > 1 {
+            (0..self.key_columns().len())
+                .map(|index| {
+                    let index = syn::Index::from(index);
+                    quote! {.bind(&id.as_raw().#index)}
+                })
+                .collect()
+        } else {
+            quote! {.bind(id.as_raw())}
+        }
+    }
+
+    /// The `WHERE` condition that selects a single item by id.
+    pub fn filter(&self) -> String {
+        let mut conditions = self.key_conditions();
+        conditions.extend(self.visible_conditions());
+        conditions.join(" AND ")
+    }
+
+    /// The `WHERE` condition that selects every item.
+    pub fn list_filter(&self) -> String {
+        let mut conditions = Vec::new();
+        if self.options.tenant.is_some() {
+            conditions.push(format!("{}=?", self.column(TENANT_FIELD_NAME)));
+        }
+        conditions.extend(self.visible_conditions());
+
+        if conditions.is_empty() {
+            "1=1".to_string()
+        } else {
+            conditions.join(" AND ")
+        }
+    }
+
+    /// The conditions that hide soft deleted and expired rows.
+    fn visible_conditions(&self) -> Vec<String> {
+        let mut conditions = Vec::new();
+        if self.options.soft_delete {
+            conditions.push(format!("{} IS NULL", self.column(DELETED_AT_FIELD_NAME)));
+        }
+        if self.options.expires {
+            let expires_at = self.column(EXPIRES_AT_FIELD_NAME);
+            conditions.push(format!("({expires_at} IS NULL OR {expires_at} > ?)"));
+        }
+        conditions
+    }
+
+    /// The `WHERE` condition that selects a single soft deleted item by id.
+    pub fn deleted_filter(&self) -> String {
+        let mut conditions = self.key_conditions();
+        conditions.push(format!(
+            "{} IS NOT NULL",
+            self.column(DELETED_AT_FIELD_NAME)
+        ));
+        conditions.join(" AND ")
+    }
+
+    /// The `WHERE` condition that selects a single row by id within the tenant, whether it is
+    /// visible or not.
+    pub fn key_filter(&self) -> String {
+        self.key_conditions().join(" AND ")
+    }
+
+    /// `.bind()` calls for [`Table::filter`], given an `id` variable.
+    pub fn filter_binds(&self) -> TokenStream {
+        let mut binds = self.key_filter_binds();
+        if self.options.expires {
+            binds.extend(quote! {.bind(::storage_noodle_sql::macro_helpers::unix_now())});
+        }
+        binds
+    }
+
+    /// `.bind()` calls for [`Table::list_filter`].
+    pub fn list_filter_binds(&self) -> TokenStream {
+        let mut binds = TokenStream::new();
+        if self.options.tenant.is_some() {
+            binds.extend(quote! {.bind(&storage.tenant)});
+        }
+        if self.options.expires {
+            binds.extend(quote! {.bind(::storage_noodle_sql::macro_helpers::unix_now())});
+        }
+        binds
+    }
+
+    /// `.bind()` calls for [`Table::key_filter`], given an `id` variable.
+    pub fn key_filter_binds(&self) -> TokenStream {
+        let mut binds = self.id_binds();
+        if self.options.tenant.is_some() {
+            binds.extend(quote! {.bind(&storage.tenant)});
+        }
+        binds
+    }
+
+    /// `.bind()` calls for [`Table::deleted_filter`], given an `id` variable.
+    pub fn deleted_filter_binds(&self) -> TokenStream {
+        self.key_filter_binds()
+    }
+
+    /// The `WHERE` condition that selects every expired row.
+    pub fn expired_filter(&self) -> String {
+        let mut conditions = vec![format!("{} <= ?", self.column(EXPIRES_AT_FIELD_NAME))];
+        if self.options.tenant.is_some() {
+            conditions.push(format!("{}=?", self.column(TENANT_FIELD_NAME)));
+        }
+        conditions.join(" AND ")
+    }
+
+    /// `.bind()` calls for [`Table::expired_filter`].
+    pub fn expired_filter_binds(&self) -> TokenStream {
+        let mut binds = quote! {.bind(::storage_noodle_sql::macro_helpers::unix_now())};
+        if self.options.tenant.is_some() {
+            binds.extend(quote! {.bind(&storage.tenant)});
+        }
+        binds
+    }
+
+    /// Wraps the body of a create, update, or delete in calls to the struct's `Hooks` (if it has
+    /// them). `body` evaluates to a `Result<T, sqlx::Error>` without using `?`, and `after` can
+    /// access its `T` as `result`. Returns (error type, wrapped body).
+    pub fn with_hooks(
+        &self,
+        body: TokenStream,
+        before: &TokenStream,
+        after: &TokenStream,
+    ) -> (TokenStream, TokenStream) {
+        if !self.options.hooks {
+            return (quote! {::sqlx::Error}, body);
+        }
+
+        let error = quote! {
+            ::storage_noodle_sql::macro_helpers::hooks::HookError<
+                ::sqlx::Error,
+                <Self as ::storage_noodle_sql::macro_helpers::hooks::Hooks>::ValidationError,
+            >
+        };
+        let body = quote! {
+            #before.map_err(::storage_noodle_sql::macro_helpers::hooks::HookError::Validation)?;
+
+            let result = { #body }
+                .map_err(::storage_noodle_sql::macro_helpers::hooks::HookError::Storage)?;
+
+            #after;
+            Ok(result)
+        };
+
+        (error, body)
+    }
 }
@@ -0,0 +1,153 @@
+use crate::attr::for_each_attr;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// The operations that `Storable` can implement, by the name used in `only(...)`.
+const OPERATIONS: [&str; 5] = ["create", "read", "update", "delete", "list"];
+
+/// Implementation of [`crate::Storable`].
+pub fn storable(item: &syn::ItemStruct) -> TokenStream {
+    let operations = match operations(item) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let mut output = crate::schema::sql_table(item);
+    output.extend(for_each_attr(item, from_row_impl));
+
+    for operation in operations {
+        output.extend(match operation.as_str() {
+            "create" => crate::crud::create(item),
+            "read" => crate::crud::read(item),
+            "update" => crate::crud::update(item),
+            "delete" => crate::crud::delete(item),
+            _ => crate::list::list(item),
+        });
+    }
+
+    output
+}
+
+/// Parses the operations to implement from the `storable` attribute (all of them if there isn't
+/// one).
+fn operations(item: &syn::ItemStruct) -> syn::Result<Vec<String>> {
+    let mut only: Option<Vec<String>> = None;
+
+    for attr in &item.attrs {
+        if !attr.path().is_ident("storable") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("only") {
+                return Err(meta.error("unknown `storable` option"));
+            }
+
+            let operations = only.get_or_insert_with(Vec::new);
+            meta.parse_nested_meta(|operation| {
+                let name = operation
+                    .path
+                    .get_ident()
+                    .map(ToString::to_string)
+                    .filter(|name| OPERATIONS.contains(&name.as_str()))
+                    .ok_or_else(|| {
+                        operation.error(
+                            "expected one of `create`, `read`, `update`, `delete`, or `list`",
+                        )
+                    })?;
+                if !operations.contains(&name) {
+                    operations.push(name);
+                }
+                Ok(())
+            })
+        })?;
+    }
+
+    Ok(only.unwrap_or_else(|| OPERATIONS.iter().map(ToString::to_string).collect()))
+}
+
+/// Per-attribute implementation of `sqlx::FromRow` for [`storable`].
+fn from_row_impl(
+    item: &syn::ItemStruct,
+    backing_db: &syn::Type,
+    raw_id: &syn::Type,
+) -> TokenStream {
+    let syn::ItemStruct { ident, fields, .. } = item;
+
+    // Get the raw id generic from attributes (if it exists).
+    let raw_id_generic = match crate::attr::raw_id_attr(item).transpose() {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    // Split generics.
+    let type_generics = match crate::attr::split_generics_with_raw_id_attr(item, raw_id) {
+        Ok((_, type_generics, _)) => type_generics,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    // The generics of the impl: the struct's (without the raw id generic), and the row lifetime.
+    let mut generics = item.generics.clone();
+    generics.params = generics
+        .params
+        .into_iter()
+        .filter(|param| {
+            !matches!(param, syn::GenericParam::Type(ty) if Some(&ty.ident) == raw_id_generic.as_ref())
+        })
+        .collect();
+    generics.params.insert(0, syn::parse_quote! {'__row});
+
+    // The columns, which are selected in the same order as the fields.
+    let columns = match crate::attr::Options::from_item(item)
+        .and_then(|options| crate::sql::Column::from_fields(fields, &options))
+    {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    // Every field is decoded from its column, apart from skipped fields, which are filled with
+    // `Default::default()`. Postgres folds unquoted names to lower case, so columns are read by
+    // index instead of by name.
+    let mut decodes = Vec::new();
+    let mut columns = columns.iter().enumerate().peekable();
+    for (i, field) in fields.iter().enumerate() {
+        let ty = match &raw_id_generic {
+            Some(raw_id_generic) => crate::attr::make_concrete(&field.ty, raw_id_generic, raw_id),
+            None => field.ty.clone(),
+        };
+        let ident = field
+            .ident
+            .clone()
+            .unwrap_or_else(|| syn::Ident::new(&i.to_string(), proc_macro2::Span::mixed_site()));
+
+        if let Some((index, _)) = columns.next_if(|(_, column)| column.ident == ident) {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(syn::parse_quote! {
+                    #ty: ::sqlx::Decode<'__row, #backing_db> + ::sqlx::Type<#backing_db>
+                });
+            decodes.push(quote! {#ident: ::sqlx::Row::try_get(row, #index)?});
+        } else {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(syn::parse_quote! {#ty: ::core::default::Default});
+            decodes.push(quote! {#ident: ::core::default::Default::default()});
+        }
+    }
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    // Implement the trait.
+    quote! {
+        impl #impl_generics ::sqlx::FromRow<'__row, <#backing_db as ::sqlx::Database>::Row> for #ident #type_generics #where_clause
+        {
+            fn from_row(row: &'__row <#backing_db as ::sqlx::Database>::Row) -> ::core::result::Result<Self, ::sqlx::Error> {
+                ::core::result::Result::Ok(Self {
+                    #(#decodes,)*
+                })
+            }
+        }
+    }
+}
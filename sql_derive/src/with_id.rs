@@ -0,0 +1,131 @@
+use crate::attr::for_each_attr;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Implementation of [`crate::CreateWithId`].
+pub fn create_with_id(item: &syn::ItemStruct) -> TokenStream {
+    for_each_attr(item, create_with_id_impl)
+}
+
+/// Per-attribute implementation for [`create_with_id`].
+fn create_with_id_impl(
+    item: &syn::ItemStruct,
+    backing_db: &syn::Type,
+    raw_id: &syn::Type,
+) -> TokenStream {
+    let syn::ItemStruct { ident, .. } = item.clone();
+
+    // Split generics.
+    let (impl_generics, type_generics, where_clause) =
+        match crate::attr::split_generics_with_raw_id_attr(item, raw_id) {
+            Ok(v) => v,
+            Err(e) => return e.to_compile_error(),
+        };
+
+    // The table description.
+    let table = match crate::attr::Options::from_item(item)
+        .and_then(|options| crate::sql::Table::new(item, &options, backing_db, raw_id))
+    {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
+    };
+    let storage = &table.storage;
+    let pool = &table.pool;
+
+    // List of columns to insert, including the id.
+    let insert_columns = table.insert_with_id_columns();
+
+    // The SQL query to run.
+    let query = {
+        let query = format!(
+            "
+                INSERT INTO {} ({})
+                VALUES ({});
+            ",
+            table.quoted_name(),
+            table.column_list(&insert_columns), // List of column names (in order).
+            (0..insert_columns.len())
+                .map(|_| "?")
+                .collect::<Vec<_>>()
+                .join(", "), // List of "?" - to be filled in with bind calls.
+        );
+        table.query(&query)
+    };
+
+    // List of `.bind()` calls to run on the query.
+    let bind_calls = table.insert_with_id_binds();
+
+    // The SQL query that counts the tenant's rows with the id (soft deleted and expired rows still
+    // hold on to their id).
+    let exists_query = table.query(&format!(
+        "SELECT COUNT(*) FROM {} WHERE {};",
+        table.quoted_name(),
+        table.key_filter()
+    ));
+    let exists_binds = table.key_filter_binds();
+
+    // Moves the id sequence past the new id (if the database generates the ids).
+    let created = table.advance_id_sequence().map_or_else(
+        || quote! {Ok(Some(()))},
+        |advance| quote! {#advance.map(|()| Some(()))},
+    );
+
+    // The method body, wrapped in hook calls (if any).
+    let (error, body) = table.with_hooks(
+        quote! {
+            // Build & execute the query.
+            let result = ::sqlx::query(#query)#bind_calls.execute(&#pool).await;
+
+            match result {
+                //= traits/spec.md#create-with-id-trait
+                //# * In the case of a full success, the future MUST return `Ok(Some(()))`.
+                Ok(_) => #created,
+
+                //= traits/spec.md#create-with-id-trait
+                //# * In the case of a partial success, where the operation succeeded, but an item with the id already exists, the future MUST return `Ok(None)` and leave the existing item unchanged.
+                // Unique indexes can conflict too, so check that it's the id that already exists.
+                Err(e) if ::storage_noodle_sql::error::ConstraintError::is_conflict(&e) => {
+                    match ::sqlx::query_scalar::<_, i64>(#exists_query)#exists_binds.fetch_one(&#pool).await {
+                        Ok(0) => Err(e),
+                        Ok(_) => Ok(None),
+                        Err(e) => Err(e),
+                    }
+                }
+
+                //= traits/spec.md#create-with-id-trait
+                //# * In the case of a failure, the future MUST return `Err()`.
+                Err(e) => Err(e),
+            }
+        },
+        &quote! {
+            ::storage_noodle_sql::macro_helpers::hooks::Hooks::before_create(self)
+        },
+        &quote! {
+            if result.is_some() {
+                ::storage_noodle_sql::macro_helpers::hooks::Hooks::after_create(self, &*id);
+            }
+        },
+    );
+
+    // Implement the trait.
+    quote! {
+        impl #impl_generics ::storage_noodle_sql::macro_helpers::CreateWithId<#storage> for #ident #type_generics #where_clause
+        {
+            type Error = #error;
+
+            fn create_with_id<'a>(
+                &'a self,
+                storage: impl ::core::ops::Deref<Target = #storage>
+                + ::core::marker::Send
+                + 'a,
+                id: impl ::core::ops::Deref<Target = ::storage_noodle_sql::macro_helpers::AssocId<Self, #raw_id>>
+                + ::core::marker::Send
+                + 'a,
+            ) -> impl ::core::future::Future<Output = ::core::result::Result<::core::option::Option<()>, Self::Error>> + ::core::marker::Send {
+                async move {
+                    #body
+                }
+            }
+        }
+    }
+}
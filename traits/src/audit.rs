@@ -0,0 +1,331 @@
+//! Append-only audit logging of every change made through a backing storage.
+
+use core::{fmt, ops::Deref};
+use std::{io::Write as _, sync::Mutex, time::SystemTime};
+
+use crate::{AssocId, BackingStorage, Create, Delete, Read, Update, boxed};
+
+/// The kind of change an [`AuditRecord`] describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum AuditOperation {
+    /// An item was created.
+    Create,
+
+    /// An item was updated.
+    Update,
+
+    /// An item was deleted.
+    Delete,
+}
+
+impl AuditOperation {
+    /// Returns the name of the operation.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// A single entry in the audit log.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AuditRecord<RawId> {
+    /// Who made the change.
+    pub actor: String,
+
+    /// When the change was made.
+    pub timestamp: SystemTime,
+
+    /// The name of the type that was changed.
+    pub type_name: String,
+
+    /// The id of the item that was changed.
+    pub id: RawId,
+
+    /// The kind of change.
+    pub operation: AuditOperation,
+
+    /// The item before the change ([`None`] for creates).
+    pub before: Option<serde_json::Value>,
+
+    /// The item after the change ([`None`] for deletes).
+    pub after: Option<serde_json::Value>,
+}
+
+/// A destination for [`AuditRecord`]s.
+pub trait AuditSink<RawId> {
+    /// The error type that can be returned from [`AuditSink::record`].
+    type Error;
+
+    /// Appends a record to the audit log.
+    fn record(
+        &self,
+        record: &AuditRecord<RawId>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+}
+
+/// A [`BackingStorage`] wrapper that records every create, update, and delete in an
+/// [`AuditSink`].
+///
+/// Reads are passed through without being recorded.
+#[derive(Debug, Clone)]
+pub struct AuditedBacking<S, Sink> {
+    /// The wrapped backing storage.
+    pub inner: S,
+
+    /// The sink that records are written to.
+    pub sink: Sink,
+
+    /// The actor that changes are attributed to.
+    pub actor: String,
+}
+
+impl<S, Sink> AuditedBacking<S, Sink> {
+    /// Create a new instance.
+    pub const fn new(inner: S, sink: Sink, actor: String) -> Self {
+        Self { inner, sink, actor }
+    }
+
+    /// Builds a record for a change to an item of type `T`.
+    fn record<T: ?Sized, RawId>(
+        &self,
+        id: RawId,
+        operation: AuditOperation,
+        before: Option<serde_json::Value>,
+        after: Option<serde_json::Value>,
+    ) -> AuditRecord<RawId> {
+        AuditRecord {
+            actor: self.actor.clone(),
+            timestamp: SystemTime::now(),
+            type_name: core::any::type_name::<T>().to_string(),
+            id,
+            operation,
+            before,
+            after,
+        }
+    }
+}
+
+impl<S: BackingStorage, Sink> BackingStorage for AuditedBacking<S, Sink> {
+    type RawId = S::RawId;
+}
+
+/// Errors that can be returned by an [`AuditedBacking`].
+#[derive(Debug)]
+pub enum AuditError<E, SinkError> {
+    /// The wrapped backing storage failed.
+    Storage(E),
+
+    /// The audit sink failed. The change has already been made at this point.
+    Sink(SinkError),
+
+    /// The item couldn't be serialized.
+    Payload(serde_json::Error),
+}
+
+impl<E: fmt::Display, SinkError: fmt::Display> fmt::Display for AuditError<E, SinkError> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Storage(e) => write!(f, "storage error: {e}"),
+            Self::Sink(e) => write!(f, "audit sink error: {e}"),
+            Self::Payload(e) => write!(f, "failed to serialize audit payload: {e}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display, SinkError: fmt::Debug + fmt::Display> core::error::Error
+    for AuditError<E, SinkError>
+{
+}
+
+impl<T, S, Sink> Create<AuditedBacking<S, Sink>> for T
+where
+    T: Create<S> + serde::Serialize + Send + Sync,
+    S: BackingStorage + Sync,
+    S::RawId: Clone + Send,
+    Sink: AuditSink<S::RawId> + Sync,
+{
+    type Error = AuditError<T::Error, Sink::Error>;
+
+    async fn create<'a>(
+        &'a self,
+        storage: impl Deref<Target = AuditedBacking<S, Sink>> + 'a + Send,
+    ) -> Result<AssocId<Self, S::RawId>, Self::Error> {
+        let after = serde_json::to_value(self).map_err(AuditError::Payload)?;
+
+        let id = boxed(<T as Create<S>>::create(self, &storage.inner))
+            .await
+            .map_err(AuditError::Storage)?;
+
+        let record = storage.record::<T, _>(
+            id.as_raw().clone(),
+            AuditOperation::Create,
+            None,
+            Some(after),
+        );
+        storage
+            .sink
+            .record(&record)
+            .await
+            .map_err(AuditError::Sink)?;
+
+        Ok(id)
+    }
+}
+
+impl<T, S, Sink> Read<AuditedBacking<S, Sink>> for T
+where
+    T: Read<S> + Sync,
+    S: BackingStorage + Sync,
+    S::RawId: Sync,
+    Sink: Sync,
+{
+    type Error = T::Error;
+
+    async fn read(
+        storage: impl Deref<Target = AuditedBacking<S, Sink>> + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> Result<Option<Self>, Self::Error> {
+        <T as Read<S>>::read(&storage.inner, &*id).await
+    }
+}
+
+impl<T, S, Sink> Update<AuditedBacking<S, Sink>> for T
+where
+    T: Read<S> + Update<S, Error = <T as Read<S>>::Error> + serde::Serialize + Sync,
+    S: BackingStorage + Sync,
+    S::RawId: Clone + Send + Sync,
+    Sink: AuditSink<S::RawId> + Sync,
+{
+    type Error = AuditError<<T as Read<S>>::Error, Sink::Error>;
+
+    async fn update<'a>(
+        &'a self,
+        storage: impl Deref<Target = AuditedBacking<S, Sink>> + 'a + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> Result<Option<()>, Self::Error> {
+        let after = serde_json::to_value(self).map_err(AuditError::Payload)?;
+
+        // Read the item before it is changed.
+        let Some(before) = <T as Read<S>>::read(&storage.inner, &*id)
+            .await
+            .map_err(AuditError::Storage)?
+        else {
+            return Ok(None);
+        };
+        let before = serde_json::to_value(before).map_err(AuditError::Payload)?;
+
+        if boxed(<T as Update<S>>::update(self, &storage.inner, &*id))
+            .await
+            .map_err(AuditError::Storage)?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let record = storage.record::<T, _>(
+            id.as_raw().clone(),
+            AuditOperation::Update,
+            Some(before),
+            Some(after),
+        );
+        storage
+            .sink
+            .record(&record)
+            .await
+            .map_err(AuditError::Sink)?;
+
+        Ok(Some(()))
+    }
+}
+
+impl<T, S, Sink> Delete<AuditedBacking<S, Sink>> for T
+where
+    T: Read<S> + Delete<S, Error = <T as Read<S>>::Error> + serde::Serialize + Sync,
+    S: BackingStorage + Sync,
+    S::RawId: Clone + Send + Sync,
+    Sink: AuditSink<S::RawId> + Sync,
+{
+    type Error = AuditError<<T as Read<S>>::Error, Sink::Error>;
+
+    async fn delete(
+        storage: impl Deref<Target = AuditedBacking<S, Sink>> + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> Result<Option<()>, Self::Error> {
+        // Read the item before it is deleted.
+        let Some(before) = <T as Read<S>>::read(&storage.inner, &*id)
+            .await
+            .map_err(AuditError::Storage)?
+        else {
+            return Ok(None);
+        };
+        let before = serde_json::to_value(before).map_err(AuditError::Payload)?;
+
+        if boxed(<T as Delete<S>>::delete(&storage.inner, &*id))
+            .await
+            .map_err(AuditError::Storage)?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let record = storage.record::<T, _>(
+            id.as_raw().clone(),
+            AuditOperation::Delete,
+            Some(before),
+            None,
+        );
+        storage
+            .sink
+            .record(&record)
+            .await
+            .map_err(AuditError::Sink)?;
+
+        Ok(Some(()))
+    }
+}
+
+/// An [`AuditSink`] that appends records to a file as JSON lines.
+#[derive(Debug)]
+pub struct JsonLinesSink {
+    /// The file that records are appended to.
+    file: Mutex<std::fs::File>,
+}
+
+impl JsonLinesSink {
+    /// Opens (or creates) a file to append records to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be opened.
+    pub fn open(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl<RawId: serde::Serialize + Sync> AuditSink<RawId> for JsonLinesSink {
+    type Error = std::io::Error;
+
+    async fn record(&self, record: &AuditRecord<RawId>) -> Result<(), Self::Error> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        // A poisoned lock only means another write panicked, the file is still usable.
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        file.write_all(&line)?;
+        file.flush()
+    }
+}
@@ -0,0 +1,193 @@
+//! A blocking (synchronous) API, for callers that don't run an async runtime.
+//!
+//! The traits in this module mirror the CRUD traits, and are implemented for every type that
+//! implements the async trait for `S`. Their futures are driven on the internal runtime of a
+//! [`Blocking`] storage, so they must not be called from within another async runtime.
+
+use crate::{AssocId, BackingStorage, Items};
+
+/// A backing storage wrapper that drives futures on an internal (single threaded) runtime.
+#[derive(Debug)]
+pub struct Blocking<S> {
+    /// The wrapped backing storage.
+    pub inner: S,
+
+    /// The runtime that futures are driven on.
+    runtime: tokio::runtime::Runtime,
+}
+
+impl<S> Blocking<S> {
+    /// Create a new instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the runtime couldn't be built.
+    pub fn new(inner: S) -> std::io::Result<Self> {
+        Ok(Self {
+            inner,
+            runtime: runtime()?,
+        })
+    }
+
+    /// Create a new instance, building the backing storage on the internal runtime.
+    ///
+    /// Storages that spawn background tasks while they are built (e.g. `sqlx` pools) must be built
+    /// this way, so the tasks outlive the call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the runtime couldn't be built, or `connect` fails.
+    pub fn connect<E: From<std::io::Error>>(
+        connect: impl Future<Output = Result<S, E>>,
+    ) -> Result<Self, E> {
+        let runtime = runtime()?;
+        let inner = runtime.block_on(connect)?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Drives a future to completion on the internal runtime.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
+
+/// Builds the internal runtime of a [`Blocking`] storage.
+fn runtime() -> std::io::Result<tokio::runtime::Runtime> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+}
+
+/// Blocking version of [`crate::Create`].
+pub trait Create<S: BackingStorage> {
+    /// The error type that can be returned from [`Create::create`].
+    type Error;
+
+    /// Creates a new item in the storage backend. Returns the Id of the new item.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails.
+    fn create(&self, storage: &Blocking<S>) -> Result<AssocId<Self, S::RawId>, Self::Error>;
+}
+
+impl<T: crate::Create<S>, S: BackingStorage + Sync> Create<S> for T {
+    type Error = T::Error;
+
+    fn create(&self, storage: &Blocking<S>) -> Result<AssocId<Self, S::RawId>, Self::Error> {
+        storage.block_on(<T as crate::Create<S>>::create(self, &storage.inner))
+    }
+}
+
+/// Blocking version of [`crate::Read`].
+pub trait Read<S: BackingStorage>: Sized {
+    /// The error type that can be returned from [`Read::read`].
+    type Error;
+
+    /// Reads an item from the storage backend. Returns the item.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails.
+    fn read(
+        storage: &Blocking<S>,
+        id: &AssocId<Self, S::RawId>,
+    ) -> Result<Option<Self>, Self::Error>;
+}
+
+impl<T: crate::Read<S>, S: BackingStorage + Sync> Read<S> for T
+where
+    S::RawId: Sync,
+{
+    type Error = T::Error;
+
+    fn read(
+        storage: &Blocking<S>,
+        id: &AssocId<Self, S::RawId>,
+    ) -> Result<Option<Self>, Self::Error> {
+        storage.block_on(<T as crate::Read<S>>::read(&storage.inner, id))
+    }
+}
+
+/// Blocking version of [`crate::Update`].
+pub trait Update<S: BackingStorage> {
+    /// The error type that can be returned from [`Update::update`].
+    type Error;
+
+    /// Updates an item in the storage backend. Will return [`None`] if the item doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails.
+    fn update(
+        &self,
+        storage: &Blocking<S>,
+        id: &AssocId<Self, S::RawId>,
+    ) -> Result<Option<()>, Self::Error>;
+}
+
+impl<T: crate::Update<S>, S: BackingStorage + Sync> Update<S> for T
+where
+    S::RawId: Sync,
+{
+    type Error = T::Error;
+
+    fn update(
+        &self,
+        storage: &Blocking<S>,
+        id: &AssocId<Self, S::RawId>,
+    ) -> Result<Option<()>, Self::Error> {
+        storage.block_on(<T as crate::Update<S>>::update(self, &storage.inner, id))
+    }
+}
+
+/// Blocking version of [`crate::Delete`].
+pub trait Delete<S: BackingStorage>: Sized {
+    /// The error type that can be returned from [`Delete::delete`].
+    type Error;
+
+    /// Deletes an item from the storage backend. Will return [`None`] if the item doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails.
+    fn delete(
+        storage: &Blocking<S>,
+        id: &AssocId<Self, S::RawId>,
+    ) -> Result<Option<()>, Self::Error>;
+}
+
+impl<T: crate::Delete<S>, S: BackingStorage + Sync> Delete<S> for T
+where
+    S::RawId: Sync,
+{
+    type Error = T::Error;
+
+    fn delete(
+        storage: &Blocking<S>,
+        id: &AssocId<Self, S::RawId>,
+    ) -> Result<Option<()>, Self::Error> {
+        storage.block_on(<T as crate::Delete<S>>::delete(&storage.inner, id))
+    }
+}
+
+/// Blocking version of [`crate::List`].
+pub trait List<S: BackingStorage>: Sized {
+    /// The error type that can be returned from [`List::list`].
+    type Error;
+
+    /// Reads every item from the storage backend. Returns the items and their Ids.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails.
+    fn list(storage: &Blocking<S>) -> Result<Items<Self, S::RawId>, Self::Error>;
+}
+
+impl<T: crate::List<S>, S: BackingStorage + Sync> List<S> for T {
+    type Error = T::Error;
+
+    fn list(storage: &Blocking<S>) -> Result<Items<Self, S::RawId>, Self::Error> {
+        storage.block_on(<T as crate::List<S>>::list(&storage.inner))
+    }
+}
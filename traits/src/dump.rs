@@ -0,0 +1,477 @@
+//! A portable archive format for backing storages, so a backup can be restored into a different
+//! backend.
+//!
+//! An archive is a directory that holds:
+//!
+//! * `manifest.json`: the format version, and the name, number of items and SHA-256 checksum of
+//!   every section.
+//! * `<section>.ndjson`: a line for every item, holding its id, the SHA-256 checksum of its
+//!   encoding, and either the item itself (`item`) or the path of a blob that holds it (`blob`).
+//! * `<section>/<index>`: the blobs of a section.
+//!
+//! [`dump`] writes every item of a type (as returned by [`List`]) to a section, and [`restore`] or
+//! [`restore_with_ids`] create them in another backing storage. Checksums are verified before any
+//! item is restored.
+
+use core::fmt;
+use std::{io::Write as _, path::PathBuf};
+
+use sha2::Digest as _;
+
+use crate::{AssocId, BackingStorage, Create, CreateWithId, Items, List, boxed, migrate::IdMap};
+
+/// The version of the archive format that is written, and the latest version that can be read.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The name of the manifest file of an archive.
+const MANIFEST: &str = "manifest.json";
+
+/// How an item is stored in an archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Encoded {
+    /// Stored in the section file, as JSON.
+    Record(serde_json::Value),
+
+    /// Stored in a file of its own, as raw bytes.
+    Blob(Vec<u8>),
+}
+
+/// A type that can be stored in an archive.
+///
+/// Implemented as a [`Encoded::Record`] for every type that implements [`serde::Serialize`] and
+/// [`serde::Deserialize`].
+pub trait Archived: Sized {
+    /// Encodes the item.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the item can't be encoded.
+    fn encode(&self) -> serde_json::Result<Encoded>;
+
+    /// Decodes an item.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the item can't be decoded.
+    fn decode(encoded: Encoded) -> serde_json::Result<Self>;
+}
+
+impl<T: serde::Serialize + serde::de::DeserializeOwned> Archived for T {
+    fn encode(&self) -> serde_json::Result<Encoded> {
+        serde_json::to_value(self).map(Encoded::Record)
+    }
+
+    fn decode(encoded: Encoded) -> serde_json::Result<Self> {
+        match encoded {
+            Encoded::Record(value) => serde_json::from_value(value),
+            Encoded::Blob(bytes) => serde_json::from_slice(&bytes),
+        }
+    }
+}
+
+/// The manifest of an archive.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Manifest {
+    /// The version of the archive format.
+    version: u32,
+
+    /// The sections of the archive.
+    sections: Vec<Section>,
+}
+
+/// A section of an archive.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Section {
+    /// The name of the section.
+    name: String,
+
+    /// The number of items in the section.
+    count: usize,
+
+    /// The SHA-256 checksum of the section file.
+    sha256: String,
+}
+
+/// A line of a section file.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Line<RawId> {
+    /// The id of the item.
+    id: RawId,
+
+    /// The SHA-256 checksum of the encoded item.
+    sha256: String,
+
+    /// The item, if it is a [`Encoded::Record`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    item: Option<serde_json::Value>,
+
+    /// The path of the blob that holds the item, if it is a [`Encoded::Blob`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    blob: Option<String>,
+}
+
+/// Returns the hex encoded SHA-256 checksum of the bytes.
+fn sha256(bytes: &[u8]) -> String {
+    hex::encode(sha2::Sha256::digest(bytes))
+}
+
+/// Returns an error if the section name has characters other than ASCII letters, digits, `-` and
+/// `_`, so it is always a valid file name.
+fn check_name(name: &str) -> Result<(), ArchiveError> {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        Ok(())
+    } else {
+        Err(ArchiveError::InvalidName(name.to_string()))
+    }
+}
+
+/// An archive directory.
+#[derive(Debug)]
+pub struct Archive {
+    /// The directory of the archive.
+    path: PathBuf,
+
+    /// The sections of the archive, with their number of items and checksum.
+    sections: Vec<(String, usize, String)>,
+}
+
+impl Archive {
+    /// Creates a new (empty) archive. The directory must not exist yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory can't be created.
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self, ArchiveError> {
+        let archive = Self {
+            path: path.into(),
+            sections: Vec::new(),
+        };
+        std::fs::create_dir(&archive.path)?;
+        archive.write_manifest()?;
+        Ok(archive)
+    }
+
+    /// Opens an existing archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest can't be read, or was written by a newer version of the
+    /// format.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, ArchiveError> {
+        let path = path.into();
+        let manifest: Manifest = serde_json::from_slice(&std::fs::read(path.join(MANIFEST))?)?;
+        if manifest.version > FORMAT_VERSION {
+            return Err(ArchiveError::UnsupportedVersion(manifest.version));
+        }
+        for section in &manifest.sections {
+            check_name(&section.name)?;
+        }
+
+        Ok(Self {
+            path,
+            sections: manifest
+                .sections
+                .into_iter()
+                .map(|section| (section.name, section.count, section.sha256))
+                .collect(),
+        })
+    }
+
+    /// Iterates over the name and number of items of every section.
+    pub fn sections(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.sections
+            .iter()
+            .map(|(name, count, _)| (name.as_str(), *count))
+    }
+
+    /// Writes the items to a new section.
+    ///
+    /// Section names may only contain ASCII letters, digits, `-` and `_`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is invalid or taken, or an item can't be encoded or written.
+    pub fn write<T: Archived, RawId: serde::Serialize>(
+        &mut self,
+        name: &str,
+        items: &Items<T, RawId>,
+    ) -> Result<(), ArchiveError> {
+        check_name(name)?;
+        if self.sections.iter().any(|(section, ..)| section == name) {
+            return Err(ArchiveError::DuplicateSection(name.to_string()));
+        }
+
+        let mut file = Vec::new();
+        for (index, (id, item)) in items.iter().enumerate() {
+            let line = match item.encode()? {
+                Encoded::Record(value) => Line {
+                    id: id.as_raw(),
+                    sha256: sha256(&serde_json::to_vec(&value)?),
+                    item: Some(value),
+                    blob: None,
+                },
+                Encoded::Blob(bytes) => {
+                    let blob = format!("{name}/{index}");
+                    std::fs::create_dir_all(self.path.join(name))?;
+                    std::fs::write(self.path.join(&blob), &bytes)?;
+                    Line {
+                        id: id.as_raw(),
+                        sha256: sha256(&bytes),
+                        item: None,
+                        blob: Some(blob),
+                    }
+                }
+            };
+            serde_json::to_writer(&mut file, &line)?;
+            file.push(b'\n');
+        }
+        std::fs::write(self.path.join(format!("{name}.ndjson")), &file)?;
+
+        self.sections
+            .push((name.to_string(), items.len(), sha256(&file)));
+        self.write_manifest()
+    }
+
+    /// Reads the items of a section, verifying their checksums.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the section doesn't exist, a checksum doesn't match, or an item can't
+    /// be read or decoded.
+    pub fn read<T: Archived, RawId: serde::de::DeserializeOwned>(
+        &self,
+        name: &str,
+    ) -> Result<Items<T, RawId>, ArchiveError> {
+        let (_, count, checksum) = self
+            .sections
+            .iter()
+            .find(|(section, ..)| section == name)
+            .ok_or_else(|| ArchiveError::MissingSection(name.to_string()))?;
+
+        let file = std::fs::read(self.path.join(format!("{name}.ndjson")))?;
+        if sha256(&file) != *checksum {
+            return Err(ArchiveError::ChecksumMismatch(name.to_string()));
+        }
+
+        let mut items = Vec::with_capacity(*count);
+        for (index, line) in file
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .enumerate()
+        {
+            let line: Line<RawId> = serde_json::from_slice(line)?;
+            let (encoded, checksum) = match (line.item, line.blob) {
+                (Some(value), _) => {
+                    let checksum = sha256(&serde_json::to_vec(&value)?);
+                    (Encoded::Record(value), checksum)
+                }
+                // Blobs are only read from their own path in the section directory.
+                (None, Some(blob)) if blob == format!("{name}/{index}") => {
+                    let bytes = std::fs::read(self.path.join(blob))?;
+                    let checksum = sha256(&bytes);
+                    (Encoded::Blob(bytes), checksum)
+                }
+                _ => return Err(ArchiveError::ChecksumMismatch(name.to_string())),
+            };
+            if checksum != line.sha256 {
+                return Err(ArchiveError::ChecksumMismatch(name.to_string()));
+            }
+
+            items.push((AssocId::new(line.id), T::decode(encoded)?));
+        }
+
+        if items.len() == *count {
+            Ok(items)
+        } else {
+            Err(ArchiveError::ChecksumMismatch(name.to_string()))
+        }
+    }
+
+    /// Writes the manifest, replacing the previous one.
+    fn write_manifest(&self) -> Result<(), ArchiveError> {
+        let manifest = Manifest {
+            version: FORMAT_VERSION,
+            sections: self
+                .sections
+                .iter()
+                .map(|(name, count, sha256)| Section {
+                    name: name.clone(),
+                    count: *count,
+                    sha256: sha256.clone(),
+                })
+                .collect(),
+        };
+
+        // Write to a temporary file first, so a crash never leaves a partial manifest.
+        let temporary = self.path.join(format!("{MANIFEST}.tmp"));
+        let mut file = std::fs::File::create(&temporary)?;
+        serde_json::to_writer_pretty(&mut file, &manifest)?;
+        file.write_all(b"\n")?;
+        file.sync_all()?;
+        std::fs::rename(temporary, self.path.join(MANIFEST))?;
+        Ok(())
+    }
+}
+
+/// Errors that can be returned when reading or writing an archive.
+#[derive(Debug)]
+pub enum ArchiveError {
+    /// Reading or writing a file failed.
+    Io(std::io::Error),
+
+    /// Encoding or decoding JSON failed.
+    Json(serde_json::Error),
+
+    /// The archive was written by a newer version of the format.
+    UnsupportedVersion(u32),
+
+    /// The section name is invalid.
+    InvalidName(String),
+
+    /// The archive already has a section with the name.
+    DuplicateSection(String),
+
+    /// The archive doesn't have a section with the name.
+    MissingSection(String),
+
+    /// The contents of the section don't match its checksums.
+    ChecksumMismatch(String),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {e}"),
+            Self::Json(e) => write!(f, "json error: {e}"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported archive format version {version}")
+            }
+            Self::InvalidName(name) => write!(f, "invalid section name {name:?}"),
+            Self::DuplicateSection(name) => write!(f, "duplicate section {name:?}"),
+            Self::MissingSection(name) => write!(f, "missing section {name:?}"),
+            Self::ChecksumMismatch(name) => write!(f, "checksum mismatch in section {name:?}"),
+        }
+    }
+}
+
+impl core::error::Error for ArchiveError {}
+
+impl From<std::io::Error> for ArchiveError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for ArchiveError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+/// Errors that can be returned when dumping or restoring items.
+#[derive(Debug)]
+pub enum BackupError<E> {
+    /// Reading or writing the archive failed.
+    Archive(ArchiveError),
+
+    /// The backing storage failed.
+    Storage(E),
+
+    /// An item couldn't be restored with its id, as the id is taken.
+    IdTaken,
+}
+
+impl<E: fmt::Display> fmt::Display for BackupError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Archive(e) => write!(f, "archive error: {e}"),
+            Self::Storage(e) => write!(f, "storage error: {e}"),
+            Self::IdTaken => write!(f, "id is already taken"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> core::error::Error for BackupError<E> {}
+
+impl<E> From<ArchiveError> for BackupError<E> {
+    fn from(value: ArchiveError) -> Self {
+        Self::Archive(value)
+    }
+}
+
+/// Writes every `T` in the storage to a new section of the archive. Returns the number of items.
+///
+/// # Errors
+///
+/// Returns an error if listing the items or writing the section fails.
+pub async fn dump<T, S>(
+    archive: &mut Archive,
+    name: &str,
+    storage: &S,
+) -> Result<usize, BackupError<<T as List<S>>::Error>>
+where
+    T: List<S> + Archived,
+    S: BackingStorage + Sync,
+    S::RawId: serde::Serialize,
+{
+    let items = boxed(<T as List<S>>::list(storage))
+        .await
+        .map_err(BackupError::Storage)?;
+    archive.write(name, &items)?;
+    Ok(items.len())
+}
+
+/// Creates every item of a section in the storage, under new ids. Returns the new id of every item.
+///
+/// # Errors
+///
+/// Returns an error if reading the section or creating an item fails.
+pub async fn restore<T, S>(
+    archive: &Archive,
+    name: &str,
+    storage: &S,
+) -> Result<IdMap<T, S::RawId, S::RawId>, BackupError<<T as Create<S>>::Error>>
+where
+    T: Create<S> + Archived,
+    S: BackingStorage + Sync,
+    S::RawId: serde::de::DeserializeOwned + Eq + core::hash::Hash,
+{
+    let mut ids = IdMap::new();
+    for (id, item) in archive.read::<T, S::RawId>(name)? {
+        let new_id = boxed(<T as Create<S>>::create(&item, storage))
+            .await
+            .map_err(BackupError::Storage)?;
+        ids.insert(id, new_id);
+    }
+    Ok(ids)
+}
+
+/// Creates every item of a section in the storage, under the ids they had when they were dumped.
+/// Returns the number of items.
+///
+/// # Errors
+///
+/// Returns an error if reading the section or creating an item fails, or an id is taken.
+pub async fn restore_with_ids<T, S>(
+    archive: &Archive,
+    name: &str,
+    storage: &S,
+) -> Result<usize, BackupError<<T as CreateWithId<S>>::Error>>
+where
+    T: CreateWithId<S> + Archived,
+    S: BackingStorage + Sync,
+    S::RawId: serde::de::DeserializeOwned + Sync,
+{
+    let items = archive.read::<T, S::RawId>(name)?;
+    for (id, item) in &items {
+        boxed(<T as CreateWithId<S>>::create_with_id(item, storage, id))
+            .await
+            .map_err(BackupError::Storage)?
+            .ok_or(BackupError::IdTaken)?;
+    }
+    Ok(items.len())
+}
@@ -0,0 +1,82 @@
+//! Extension methods on backing storages.
+
+use crate::{AssocId, BackingStorage, Create, Delete, Items, List, Read, Update};
+
+/// Methods that forward to the CRUD traits, so call sites can start with the backing storage
+/// (`backing.read(&id)` instead of `Cookie::read(&backing, &id)`).
+///
+/// Implemented for every [`BackingStorage`].
+pub trait StorageExt: BackingStorage + Sized {
+    /// Creates a new item. Returns the Id of the new item.
+    ///
+    /// Shorthand for [`Create::create`].
+    fn create<'a, T>(
+        &'a self,
+        item: &'a T,
+    ) -> impl Future<Output = Result<AssocId<T, Self::RawId>, T::Error>> + Send
+    where
+        T: Create<Self>,
+        Self: Sync,
+    {
+        <T as Create<Self>>::create(item, self)
+    }
+
+    /// Reads an item. Will return [`None`] if it doesn't exist.
+    ///
+    /// Shorthand for [`Read::read`].
+    fn read<T>(
+        &self,
+        id: &AssocId<T, Self::RawId>,
+    ) -> impl Future<Output = Result<Option<T>, T::Error>> + Send
+    where
+        T: Read<Self>,
+        Self: Sync,
+        Self::RawId: Sync,
+    {
+        <T as Read<Self>>::read(self, id)
+    }
+
+    /// Replaces the item with the id. Will return [`None`] if it doesn't exist.
+    ///
+    /// Shorthand for [`Update::update`].
+    fn update<'a, T>(
+        &'a self,
+        id: &'a AssocId<T, Self::RawId>,
+        item: &'a T,
+    ) -> impl Future<Output = Result<Option<()>, T::Error>> + Send
+    where
+        T: Update<Self>,
+        Self: Sync,
+        Self::RawId: Sync,
+    {
+        <T as Update<Self>>::update(item, self, id)
+    }
+
+    /// Deletes the item with the id. Will return [`None`] if it doesn't exist.
+    ///
+    /// Shorthand for [`Delete::delete`].
+    fn delete<T>(
+        &self,
+        id: &AssocId<T, Self::RawId>,
+    ) -> impl Future<Output = Result<Option<()>, T::Error>> + Send
+    where
+        T: Delete<Self>,
+        Self: Sync,
+        Self::RawId: Sync,
+    {
+        <T as Delete<Self>>::delete(self, id)
+    }
+
+    /// Reads every item of type `T`.
+    ///
+    /// Shorthand for [`List::list`].
+    fn list<T>(&self) -> impl Future<Output = Result<Items<T, Self::RawId>, T::Error>> + Send
+    where
+        T: List<Self>,
+        Self: Sync,
+    {
+        <T as List<Self>>::list(self)
+    }
+}
+
+impl<S: BackingStorage> StorageExt for S {}
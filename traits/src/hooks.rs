@@ -0,0 +1,196 @@
+//! Lifecycle hooks that run around creates, updates, and deletes.
+//!
+//! Hooks are called by the [`HookedBacking`] wrapper, and by backends that support them directly
+//! (e.g. `#[storage_noodle(hooks)]` in `storage_noodle_sql`).
+
+use core::{fmt, ops::Deref};
+
+use crate::{AssocId, BackingStorage, Create, Delete, Read, Update, boxed};
+
+/// Callbacks that run around changes to an item. Every hook does nothing by default.
+///
+/// `before_*` hooks can reject an operation by returning a [`Hooks::ValidationError`], in which
+/// case the backing storage isn't touched. `after_*` hooks only run once an operation has fully
+/// succeeded.
+pub trait Hooks: Sized {
+    /// The error type returned when a `before_*` hook rejects an operation.
+    type ValidationError;
+
+    /// Runs before the item is created.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error to reject the create.
+    fn before_create(&self) -> Result<(), Self::ValidationError> {
+        Ok(())
+    }
+
+    /// Runs after the item has been created.
+    fn after_create<RawId>(&self, _id: &AssocId<Self, RawId>) {}
+
+    /// Runs before the item with the id is replaced by this one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error to reject the update.
+    fn before_update<RawId>(
+        &self,
+        _id: &AssocId<Self, RawId>,
+    ) -> Result<(), Self::ValidationError> {
+        Ok(())
+    }
+
+    /// Runs after the item with the id has been replaced by this one.
+    fn after_update<RawId>(&self, _id: &AssocId<Self, RawId>) {}
+
+    /// Runs before the item with the id is deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error to reject the delete.
+    fn before_delete<RawId>(_id: &AssocId<Self, RawId>) -> Result<(), Self::ValidationError> {
+        Ok(())
+    }
+
+    /// Runs after the item with the id has been deleted.
+    fn after_delete<RawId>(_id: &AssocId<Self, RawId>) {}
+}
+
+/// Errors that can be returned by an operation that runs [`Hooks`].
+#[derive(Debug)]
+pub enum HookError<E, ValidationError> {
+    /// A `before_*` hook rejected the operation.
+    Validation(ValidationError),
+
+    /// The backing storage failed.
+    Storage(E),
+}
+
+impl<E: fmt::Display, ValidationError: fmt::Display> fmt::Display
+    for HookError<E, ValidationError>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation(e) => write!(f, "validation error: {e}"),
+            Self::Storage(e) => write!(f, "storage error: {e}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display, ValidationError: fmt::Debug + fmt::Display> core::error::Error
+    for HookError<E, ValidationError>
+{
+}
+
+/// A [`BackingStorage`] wrapper that runs the [`Hooks`] of every item that is created, updated, or
+/// deleted through it.
+///
+/// Reads are passed through.
+#[derive(Debug, Clone)]
+pub struct HookedBacking<S> {
+    /// The wrapped backing storage.
+    pub inner: S,
+}
+
+impl<S> HookedBacking<S> {
+    /// Create a new instance.
+    pub const fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S: BackingStorage> BackingStorage for HookedBacking<S> {
+    type RawId = S::RawId;
+}
+
+impl<T, S> Create<HookedBacking<S>> for T
+where
+    T: Create<S> + Hooks + Sync,
+    T::ValidationError: Send,
+    S: BackingStorage + Sync,
+{
+    type Error = HookError<<T as Create<S>>::Error, T::ValidationError>;
+
+    async fn create<'a>(
+        &'a self,
+        storage: impl Deref<Target = HookedBacking<S>> + 'a + Send,
+    ) -> Result<AssocId<Self, S::RawId>, Self::Error> {
+        self.before_create().map_err(HookError::Validation)?;
+
+        let id = boxed(<T as Create<S>>::create(self, &storage.inner))
+            .await
+            .map_err(HookError::Storage)?;
+
+        self.after_create(&id);
+        Ok(id)
+    }
+}
+
+impl<T, S> Read<HookedBacking<S>> for T
+where
+    T: Read<S> + Sync,
+    S: BackingStorage + Sync,
+    S::RawId: Sync,
+{
+    type Error = T::Error;
+
+    async fn read(
+        storage: impl Deref<Target = HookedBacking<S>> + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> Result<Option<Self>, Self::Error> {
+        <T as Read<S>>::read(&storage.inner, &*id).await
+    }
+}
+
+impl<T, S> Update<HookedBacking<S>> for T
+where
+    T: Update<S> + Hooks + Sync,
+    T::ValidationError: Send,
+    S: BackingStorage + Sync,
+    S::RawId: Sync,
+{
+    type Error = HookError<<T as Update<S>>::Error, T::ValidationError>;
+
+    async fn update<'a>(
+        &'a self,
+        storage: impl Deref<Target = HookedBacking<S>> + 'a + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> Result<Option<()>, Self::Error> {
+        self.before_update(&*id).map_err(HookError::Validation)?;
+
+        let result = boxed(<T as Update<S>>::update(self, &storage.inner, &*id))
+            .await
+            .map_err(HookError::Storage)?;
+
+        if result.is_some() {
+            self.after_update(&*id);
+        }
+        Ok(result)
+    }
+}
+
+impl<T, S> Delete<HookedBacking<S>> for T
+where
+    T: Delete<S> + Hooks + Sync,
+    T::ValidationError: Send,
+    S: BackingStorage + Sync,
+    S::RawId: Sync,
+{
+    type Error = HookError<<T as Delete<S>>::Error, T::ValidationError>;
+
+    async fn delete(
+        storage: impl Deref<Target = HookedBacking<S>> + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> Result<Option<()>, Self::Error> {
+        T::before_delete(&*id).map_err(HookError::Validation)?;
+
+        let result = boxed(<T as Delete<S>>::delete(&storage.inner, &*id))
+            .await
+            .map_err(HookError::Storage)?;
+
+        if result.is_some() {
+            T::after_delete(&*id);
+        }
+        Ok(result)
+    }
+}
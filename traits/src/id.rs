@@ -0,0 +1,212 @@
+//! Client-side id generation.
+//!
+//! Backings that generate ids themselves (e.g. an SQL database returning `Id`) can't hand out an
+//! id before an item is inserted. An [`IdGenerator`] produces ids up front instead, so they can be
+//! time-sortable, carry a prefix, or be known before the insert happens.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Generates ids of type `Id` for new items.
+pub trait IdGenerator<Id> {
+    /// Generates a new, unique id.
+    fn generate(&self) -> Id;
+}
+
+/// Generates random (version 4) UUIDs.
+#[cfg(feature = "uuid")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidV4;
+
+#[cfg(feature = "uuid")]
+impl IdGenerator<uuid::Uuid> for UuidV4 {
+    fn generate(&self) -> uuid::Uuid {
+        uuid::Uuid::new_v4()
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl IdGenerator<String> for UuidV4 {
+    fn generate(&self) -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+}
+
+/// Generates time-sortable (version 7) UUIDs.
+#[cfg(feature = "uuid")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidV7;
+
+#[cfg(feature = "uuid")]
+impl IdGenerator<uuid::Uuid> for UuidV7 {
+    fn generate(&self) -> uuid::Uuid {
+        uuid::Uuid::now_v7()
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl IdGenerator<String> for UuidV7 {
+    fn generate(&self) -> String {
+        uuid::Uuid::now_v7().to_string()
+    }
+}
+
+/// Generates time-sortable ULIDs.
+#[cfg(feature = "ulid")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ulid;
+
+#[cfg(feature = "ulid")]
+impl IdGenerator<ulid::Ulid> for Ulid {
+    fn generate(&self) -> ulid::Ulid {
+        ulid::Ulid::generate()
+    }
+}
+
+#[cfg(feature = "ulid")]
+impl IdGenerator<u128> for Ulid {
+    fn generate(&self) -> u128 {
+        ulid::Ulid::generate().into()
+    }
+}
+
+#[cfg(feature = "ulid")]
+impl IdGenerator<String> for Ulid {
+    fn generate(&self) -> String {
+        ulid::Ulid::generate().to_string()
+    }
+}
+
+/// Generates time-sortable 63 bit Snowflake ids.
+///
+/// Ids are made of 41 bits of milliseconds since the epoch, 10 bits of worker id, and a 12 bit
+/// sequence number. Every process that generates ids concurrently must use a different worker id.
+/// If more than 4096 ids are generated in a millisecond, the timestamp of the next ids is moved
+/// into the future.
+#[derive(Debug)]
+pub struct Snowflake {
+    /// Milliseconds since the unix epoch that timestamps are relative to.
+    epoch_ms: u64,
+
+    /// The worker id (10 bits).
+    worker: u64,
+
+    /// The timestamp and sequence number of the last id, packed like an id without a worker.
+    last: AtomicU64,
+}
+
+impl Snowflake {
+    /// 2024-01-01T00:00:00Z, in milliseconds since the unix epoch.
+    pub const DEFAULT_EPOCH_MS: u64 = 1_704_067_200_000;
+
+    /// The number of bits used by the sequence number.
+    const SEQUENCE_BITS: u32 = 12;
+
+    /// The number of bits used by the worker id.
+    const WORKER_BITS: u32 = 10;
+
+    /// Create a new generator, with timestamps relative to [`Snowflake::DEFAULT_EPOCH_MS`]. Only
+    /// the lower 10 bits of `worker` are used.
+    #[must_use]
+    pub const fn new(worker: u16) -> Self {
+        Self::with_epoch(worker, Self::DEFAULT_EPOCH_MS)
+    }
+
+    /// Create a new generator, with timestamps relative to `epoch_ms` milliseconds since the unix
+    /// epoch. Only the lower 10 bits of `worker` are used.
+    #[must_use]
+    pub const fn with_epoch(worker: u16, epoch_ms: u64) -> Self {
+        Self {
+            epoch_ms,
+            worker: worker as u64 & ((1 << Self::WORKER_BITS) - 1),
+            last: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the number of milliseconds since the epoch.
+    fn now(&self) -> u64 {
+        let since_unix = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |since| {
+                u64::try_from(since.as_millis()).unwrap_or(u64::MAX)
+            });
+        since_unix.saturating_sub(self.epoch_ms)
+    }
+}
+
+impl IdGenerator<u64> for Snowflake {
+    fn generate(&self) -> u64 {
+        let now = self.now() << Self::SEQUENCE_BITS;
+
+        // Take the next (timestamp, sequence) pair. Incrementing the packed value rolls the
+        // sequence over into the timestamp.
+        let previous = self
+            .last
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+                Some(now.max(last + 1))
+            })
+            .unwrap_or_else(|last| last);
+        let next = now.max(previous + 1);
+
+        let timestamp = next >> Self::SEQUENCE_BITS;
+        let sequence = next & ((1 << Self::SEQUENCE_BITS) - 1);
+        ((timestamp << (Self::WORKER_BITS + Self::SEQUENCE_BITS))
+            | (self.worker << Self::SEQUENCE_BITS)
+            | sequence)
+            & (u64::MAX >> 1)
+    }
+}
+
+impl IdGenerator<i64> for Snowflake {
+    fn generate(&self) -> i64 {
+        // The top bit is always clear.
+        i64::try_from(IdGenerator::<u64>::generate(self)).unwrap_or(i64::MAX)
+    }
+}
+
+impl IdGenerator<String> for Snowflake {
+    fn generate(&self) -> String {
+        IdGenerator::<u64>::generate(self).to_string()
+    }
+}
+
+/// Generates random ids, encoded as url-safe base64 and prefixed with a fixed string.
+#[cfg(feature = "random")]
+#[derive(Debug, Clone)]
+pub struct PrefixedRandom {
+    /// The prefix of every id.
+    pub prefix: String,
+
+    /// The number of random bytes in every id.
+    pub bytes: usize,
+}
+
+#[cfg(feature = "random")]
+impl PrefixedRandom {
+    /// Create a new generator.
+    #[must_use]
+    pub const fn new(prefix: String, bytes: usize) -> Self {
+        Self { prefix, bytes }
+    }
+}
+
+#[cfg(feature = "random")]
+impl Default for PrefixedRandom {
+    /// 256 random bits, without a prefix.
+    fn default() -> Self {
+        Self::new(String::new(), 32)
+    }
+}
+
+#[cfg(feature = "random")]
+impl IdGenerator<String> for PrefixedRandom {
+    fn generate(&self) -> String {
+        use base64::{Engine as _, engine::general_purpose::URL_SAFE};
+
+        let mut random = vec![0; self.bytes];
+        rand::fill(random.as_mut_slice());
+
+        let mut id = self.prefix.clone();
+        URL_SAFE.encode_string(random, &mut id);
+        id
+    }
+}
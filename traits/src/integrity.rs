@@ -0,0 +1,261 @@
+//! Referential integrity for [`AssocId`] fields.
+//!
+//! Backing storages that enforce references themselves (e.g. SQL foreign keys from
+//! `storage_noodle_sql`) don't need this module. For everything else, [`find_dangling`] scans for
+//! references to items that no longer exist, and [`IntegrityBacking`] enforces [`restrict`] or
+//! [`cascade`] when a referenced item is deleted.
+//!
+//! There are no indexes to look references up with, so [`restrict`] and [`cascade`] list the whole
+//! referencing table and filter it in memory: every delete costs O(table) per referencing type.
+//! Only deletes are checked. Creates and updates can still store a reference to an item that
+//! doesn't exist, which [`find_dangling`] finds afterwards.
+
+use core::{fmt, ops::Deref};
+
+use crate::{AssocId, BackingStorage, Delete, Items, List, Read, boxed};
+
+/// A type with [`AssocId`] fields that reference items of type `T`.
+pub trait References<T: ?Sized, RawId> {
+    /// Returns the ids of every `T` that the item references.
+    fn references(&self) -> Vec<&AssocId<T, RawId>>;
+}
+
+/// A reference to an item that doesn't exist.
+pub struct Dangling<C, T, RawId> {
+    /// The id of the item that holds the reference.
+    pub item: AssocId<C, RawId>,
+
+    /// The id of the missing item.
+    pub reference: AssocId<T, RawId>,
+}
+
+impl<C, T, RawId: fmt::Debug> fmt::Debug for Dangling<C, T, RawId> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dangling")
+            .field("item", &self.item)
+            .field("reference", &self.reference)
+            .finish()
+    }
+}
+
+impl<C, T, RawId: PartialEq> PartialEq for Dangling<C, T, RawId> {
+    fn eq(&self, other: &Self) -> bool {
+        self.item == other.item && self.reference == other.reference
+    }
+}
+
+impl<C, T, RawId: Eq> Eq for Dangling<C, T, RawId> {}
+
+/// Errors that can be returned when enforcing referential integrity.
+#[derive(Debug)]
+pub enum IntegrityError<E> {
+    /// The item is still referenced by items that restrict its deletion.
+    Restricted {
+        /// The number of items that reference it.
+        referenced_by: usize,
+    },
+
+    /// The backing storage failed.
+    Storage(E),
+}
+
+impl<E: fmt::Display> fmt::Display for IntegrityError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Restricted { referenced_by } => {
+                write!(f, "item is still referenced by {referenced_by} item(s)")
+            }
+            Self::Storage(e) => write!(f, "storage error: {e}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> core::error::Error for IntegrityError<E> {}
+
+impl<E> From<E> for IntegrityError<E> {
+    fn from(value: E) -> Self {
+        Self::Storage(value)
+    }
+}
+
+/// Scans every `C` for references to a `T` that doesn't exist.
+///
+/// # Errors
+///
+/// Returns an error if listing the `C`s or reading a `T` fails.
+pub async fn find_dangling<C, T, S>(
+    storage: &S,
+) -> Result<Vec<Dangling<C, T, S::RawId>>, <C as List<S>>::Error>
+where
+    C: List<S> + References<T, S::RawId>,
+    T: Read<S, Error = <C as List<S>>::Error>,
+    S: BackingStorage + Sync,
+    S::RawId: Clone + Sync,
+{
+    let mut dangling = Vec::new();
+
+    for (item, value) in boxed(<C as List<S>>::list(storage)).await? {
+        for reference in value.references() {
+            if boxed(<T as Read<S>>::read(storage, reference))
+                .await?
+                .is_none()
+            {
+                dangling.push(Dangling {
+                    item: item.clone(),
+                    reference: reference.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(dangling)
+}
+
+/// Returns [`IntegrityError::Restricted`] if any `C` references the `T` with the id.
+///
+/// Lists every `C`, so it takes time proportional to the number of `C`s.
+///
+/// # Errors
+///
+/// Returns an error if the `T` is referenced, or listing the `C`s fails.
+pub async fn restrict<C, T, S>(
+    storage: &S,
+    id: &AssocId<T, S::RawId>,
+) -> Result<(), IntegrityError<<C as List<S>>::Error>>
+where
+    C: List<S> + References<T, S::RawId>,
+    S: BackingStorage + Sync,
+    S::RawId: PartialEq,
+{
+    let referenced_by = boxed(<C as List<S>>::list(storage))
+        .await?
+        .iter()
+        .filter(|(_, value)| value.references().contains(&id))
+        .count();
+
+    if referenced_by == 0 {
+        Ok(())
+    } else {
+        Err(IntegrityError::Restricted { referenced_by })
+    }
+}
+
+/// Deletes every `C` that references the `T` with the id. Returns the number of items that were
+/// deleted.
+///
+/// Lists every `C`, so it takes time proportional to the number of `C`s. Items are deleted from
+/// `storage` directly, so their own references aren't enforced.
+///
+/// # Errors
+///
+/// Returns an error if listing or deleting the `C`s fails.
+pub async fn cascade<C, T, S>(
+    storage: &S,
+    id: &AssocId<T, S::RawId>,
+) -> Result<u64, IntegrityError<<C as List<S>>::Error>>
+where
+    C: List<S> + Delete<S, Error = <C as List<S>>::Error> + References<T, S::RawId>,
+    S: BackingStorage + Sync,
+    S::RawId: PartialEq + Sync,
+{
+    let mut deleted = 0;
+
+    for (item, value) in boxed(<C as List<S>>::list(storage)).await? {
+        if value.references().contains(&id)
+            && boxed(<C as Delete<S>>::delete(storage, &item))
+                .await?
+                .is_some()
+        {
+            deleted += 1;
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// A type that is referenced by other types, and enforces their references when it is deleted
+/// through an [`IntegrityBacking`].
+///
+/// The [`Delete`] impl for the wrapped storage must use the same error type.
+pub trait Referenced<S: BackingStorage>: Sized {
+    /// The error type that can be returned from [`Referenced::on_delete`].
+    type Error;
+
+    /// Runs before the item with the id is deleted. Usually calls [`restrict`] or [`cascade`] for
+    /// every type that references it, naming `S` explicitly (e.g. `cascade::<C, Self, S>`), as it
+    /// can't always be inferred through the blanket impls of the wrapper storages.
+    fn on_delete(
+        storage: &S,
+        id: &AssocId<Self, S::RawId>,
+    ) -> impl Future<Output = Result<(), IntegrityError<Self::Error>>> + Send;
+}
+
+/// A [`BackingStorage`] wrapper that runs [`Referenced::on_delete`] before every delete.
+///
+/// Reads and lists are passed through. Creates and updates aren't checked (they can reference items
+/// that don't exist), so they should go through [`IntegrityBacking::inner`].
+#[derive(Debug, Clone)]
+pub struct IntegrityBacking<S> {
+    /// The wrapped backing storage.
+    pub inner: S,
+}
+
+impl<S> IntegrityBacking<S> {
+    /// Create a new instance.
+    pub const fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S: BackingStorage> BackingStorage for IntegrityBacking<S> {
+    type RawId = S::RawId;
+}
+
+impl<T, S> Read<IntegrityBacking<S>> for T
+where
+    T: Read<S> + Sync,
+    S: BackingStorage + Sync,
+    S::RawId: Sync,
+{
+    type Error = T::Error;
+
+    async fn read(
+        storage: impl Deref<Target = IntegrityBacking<S>> + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> Result<Option<Self>, Self::Error> {
+        <T as Read<S>>::read(&storage.inner, &*id).await
+    }
+}
+
+impl<T, S> List<IntegrityBacking<S>> for T
+where
+    T: List<S>,
+    S: BackingStorage + Sync,
+{
+    type Error = T::Error;
+
+    async fn list(
+        storage: impl Deref<Target = IntegrityBacking<S>> + Send,
+    ) -> Result<Items<Self, S::RawId>, Self::Error> {
+        <T as List<S>>::list(&storage.inner).await
+    }
+}
+
+impl<T, S> Delete<IntegrityBacking<S>> for T
+where
+    T: Referenced<S> + Delete<S, Error = <T as Referenced<S>>::Error>,
+    <T as Referenced<S>>::Error: Send,
+    S: BackingStorage + Sync,
+    S::RawId: Sync,
+{
+    type Error = IntegrityError<<T as Referenced<S>>::Error>;
+
+    async fn delete(
+        storage: impl Deref<Target = IntegrityBacking<S>> + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> Result<Option<()>, Self::Error> {
+        boxed(<T as Referenced<S>>::on_delete(&storage.inner, &*id)).await?;
+
+        Ok(boxed(<T as Delete<S>>::delete(&storage.inner, &*id)).await?)
+    }
+}
@@ -1,10 +1,47 @@
 #![doc = include_str!(concat!(env!("OUT_DIR"), "/README-rustdocified.md"))]
 
-use core::{marker::PhantomData, ops::Deref};
+use core::{fmt, marker::PhantomData, ops::Deref, pin::Pin};
 
 #[cfg(feature = "sqlx")]
 pub mod sqlx;
 
+pub mod tenant;
+
+pub mod hooks;
+
+pub mod id;
+
+pub mod prefixed;
+
+pub mod reference;
+
+pub mod integrity;
+
+pub mod ext;
+
+pub mod repository;
+
+pub mod migrate;
+
+#[cfg(feature = "audit")]
+pub mod audit;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+#[cfg(feature = "dump")]
+pub mod dump;
+
+/// Boxes a future returned by a wrapped storage.
+///
+/// Awaiting it unboxed inside another `Send` future trips
+/// [rust-lang/rust#100013](https://github.com/rust-lang/rust/issues/100013).
+fn boxed<'a, F: Future + Send + 'a>(
+    future: F,
+) -> Pin<Box<dyn Future<Output = F::Output> + Send + 'a>> {
+    Box::pin(future)
+}
+
 /// A type that can store persistant data.
 pub trait BackingStorage {
     /// The id type that is used to identify specific items.
@@ -12,14 +49,15 @@ pub trait BackingStorage {
 }
 
 /// An Id that references a specific type.
-#[derive(Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+///
+/// `T` is only used at compile time, so every trait impl is bounded on `RawId` alone, and the id is
+/// [`Send`] and [`Sync`] regardless of `T`.
 pub struct AssocId<T: ?Sized, RawId> {
     /// The inner raw id.
     inner: RawId,
 
     /// Phantom data.
-    phantom: PhantomData<T>,
+    phantom: PhantomData<fn() -> T>,
 }
 
 impl<T: ?Sized, RawId> AssocId<T, RawId> {
@@ -35,6 +73,133 @@ impl<T: ?Sized, RawId> AssocId<T, RawId> {
     pub const fn as_raw(&self) -> &RawId {
         &self.inner
     }
+
+    /// Get the inner raw Id. Common raw id types can also be converted with `into()`.
+    pub fn into_raw(self) -> RawId {
+        self.inner
+    }
+
+    /// Reinterpret the id as an id of another type.
+    ///
+    /// This is safe in the memory sense, but defeats the point of [`AssocId`]: the result only
+    /// references an item if the raw id of a `T` is also a valid raw id of a `U`.
+    pub fn cast<U: ?Sized>(self) -> AssocId<U, RawId> {
+        AssocId::new(self.inner)
+    }
+}
+
+impl<T, RawId> AssocId<T, RawId> {
+    /// Reads the referenced item from a backing storage. Will return [`None`] if it doesn't exist.
+    ///
+    /// Shorthand for [`Read::read`].
+    pub fn fetch<S>(
+        &self,
+        storage: impl Deref<Target = S> + Send,
+    ) -> impl Future<Output = Result<Option<T>, T::Error>> + Send
+    where
+        S: BackingStorage<RawId = RawId>,
+        T: Read<S>,
+        RawId: Sync,
+    {
+        T::read(storage, self)
+    }
+}
+
+impl<T: ?Sized, RawId: fmt::Debug> fmt::Debug for AssocId<T, RawId> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AssocId").field(&self.inner).finish()
+    }
+}
+
+/// Displays the raw id. Use [`AssocId::prefixed`] to display it with the prefix of its type (see
+/// [`prefixed`]).
+impl<T: ?Sized, RawId: fmt::Display> fmt::Display for AssocId<T, RawId> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<T: ?Sized, RawId: Clone> Clone for AssocId<T, RawId> {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl<T: ?Sized, RawId: Copy> Copy for AssocId<T, RawId> {}
+
+impl<T: ?Sized, RawId: PartialEq> PartialEq for AssocId<T, RawId> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<T: ?Sized, RawId: Eq> Eq for AssocId<T, RawId> {}
+
+impl<T: ?Sized, RawId: PartialOrd> PartialOrd for AssocId<T, RawId> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.inner.partial_cmp(&other.inner)
+    }
+}
+
+impl<T: ?Sized, RawId: Ord> Ord for AssocId<T, RawId> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.inner.cmp(&other.inner)
+    }
+}
+
+impl<T: ?Sized, RawId: core::hash::Hash> core::hash::Hash for AssocId<T, RawId> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
+
+impl<T: ?Sized, RawId> From<RawId> for AssocId<T, RawId> {
+    fn from(value: RawId) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Implements `From<AssocId<T, Raw>>` for each raw id type `Raw`, so ids can be converted with
+/// `into()`. A generic `impl From<AssocId<T, RawId>> for RawId` is rejected by the orphan rules
+/// (the uncovered `RawId` comes before the local `AssocId`), so other raw id types have to use
+/// [`AssocId::into_raw`].
+macro_rules! impl_into_raw {
+    ($($raw:ty),* $(,)?) => {
+        $(
+            impl<T: ?Sized> From<AssocId<T, $raw>> for $raw {
+                fn from(value: AssocId<T, $raw>) -> Self {
+                    value.inner
+                }
+            }
+        )*
+    };
+}
+
+impl_into_raw!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, String
+);
+
+#[cfg(feature = "uuid")]
+impl_into_raw!(uuid::Uuid);
+
+#[cfg(feature = "ulid")]
+impl_into_raw!(ulid::Ulid);
+
+/// Serialized as the raw id. Use [`prefixed::Prefixed`] (or `#[serde(with = "...")]` with the
+/// [`prefixed`] module) to serialize it with the prefix of its type.
+#[cfg(feature = "serde")]
+impl<T: ?Sized, RawId: serde::Serialize> serde::Serialize for AssocId<T, RawId> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.inner.serialize(serializer)
+    }
+}
+
+/// Deserialized from the raw id.
+#[cfg(feature = "serde")]
+impl<'de, T: ?Sized, RawId: serde::Deserialize<'de>> serde::Deserialize<'de> for AssocId<T, RawId> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RawId::deserialize(deserializer).map(Self::new)
+    }
 }
 
 /// Trait that abstracts over creating data in a storage backend.
@@ -49,6 +214,20 @@ pub trait Create<S: BackingStorage> {
     ) -> impl Future<Output = Result<AssocId<Self, S::RawId>, Self::Error>> + Send;
 }
 
+/// Trait that abstracts over creating data with a known id in a storage backend.
+pub trait CreateWithId<S: BackingStorage>: Sized {
+    /// The error type that can be returned from [`CreateWithId::create_with_id`].
+    type Error;
+
+    /// Creates a new item with the given id in the storage backend. Will return [`None`] if an
+    /// item with the id already exists.
+    fn create_with_id<'a>(
+        &'a self,
+        storage: impl Deref<Target = S> + 'a + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + 'a + Send,
+    ) -> impl Future<Output = Result<Option<()>, Self::Error>> + Send;
+}
+
 /// Trait that abstracts over reading data from a storage backend.
 pub trait Read<S: BackingStorage>: Sized {
     /// The error type that can be returned from [`Read::read`].
@@ -85,3 +264,80 @@ pub trait Delete<S: BackingStorage>: Sized {
         id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
     ) -> impl Future<Output = Result<Option<()>, Self::Error>> + Send;
 }
+
+/// Items and their Ids, as returned by [`List::list`].
+pub type Items<T, RawId> = Vec<(AssocId<T, RawId>, T)>;
+
+/// Trait that abstracts over listing every item in a storage backend.
+pub trait List<S: BackingStorage>: Sized {
+    /// The error type that can be returned from [`List::list`].
+    type Error;
+
+    /// Reads every item from the storage backend. Returns the items and their Ids.
+    fn list(
+        storage: impl Deref<Target = S> + Send,
+    ) -> impl Future<Output = Result<Items<Self, S::RawId>, Self::Error>> + Send;
+}
+
+/// A type that implements every CRUD trait for a backing storage.
+///
+/// Implemented for every type that implements [`Create`], [`Read`], [`Update`], and [`Delete`],
+/// so generic code can take a single bound.
+pub trait Storable<S: BackingStorage>: Create<S> + Read<S> + Update<S> + Delete<S> {}
+
+impl<T, S> Storable<S> for T
+where
+    T: Create<S> + Read<S> + Update<S> + Delete<S>,
+    S: BackingStorage,
+{
+}
+
+/// Trait that abstracts over restoring soft deleted data in a storage backend.
+pub trait Restore<S: BackingStorage>: Sized {
+    /// The error type that can be returned from [`Restore::restore`].
+    type Error;
+
+    /// Restores a soft deleted item. Will return [`None`] if there is no deleted item with the id.
+    fn restore(
+        storage: impl Deref<Target = S> + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> impl Future<Output = Result<Option<()>, Self::Error>> + Send;
+}
+
+/// Trait that abstracts over permanently removing soft deleted data from a storage backend.
+pub trait Purge<S: BackingStorage>: Sized {
+    /// The error type that can be returned from [`Purge::purge`].
+    type Error;
+
+    /// Permanently removes a soft deleted item. Will return [`None`] if there is no deleted item
+    /// with the id.
+    fn purge(
+        storage: impl Deref<Target = S> + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> impl Future<Output = Result<Option<()>, Self::Error>> + Send;
+}
+
+/// Trait that abstracts over creating data that expires in a storage backend.
+pub trait CreateWithTtl<S: BackingStorage> {
+    /// The error type that can be returned from [`CreateWithTtl::create_with_ttl`].
+    type Error;
+
+    /// Creates a new item in the storage backend that expires after `ttl`. Returns the Id of the
+    /// new item.
+    fn create_with_ttl<'a>(
+        &'a self,
+        storage: impl Deref<Target = S> + 'a + Send,
+        ttl: core::time::Duration,
+    ) -> impl Future<Output = Result<AssocId<Self, S::RawId>, Self::Error>> + Send;
+}
+
+/// Trait that abstracts over removing expired data from a storage backend.
+pub trait PurgeExpired<S: BackingStorage> {
+    /// The error type that can be returned from [`PurgeExpired::purge_expired`].
+    type Error;
+
+    /// Permanently removes every expired item. Returns the number of items that were removed.
+    fn purge_expired(
+        storage: impl Deref<Target = S> + Send,
+    ) -> impl Future<Output = Result<u64, Self::Error>> + Send;
+}
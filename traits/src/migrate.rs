@@ -0,0 +1,364 @@
+//! Copying items between backing storages.
+//!
+//! [`copy_all`] copies every item of a type (as returned by [`List`]) with [`Create`], and returns
+//! an [`IdMap`] from the old ids to the new ones. Types that reference copied items are copied
+//! afterwards with [`copy_dependents`], which rewrites their [`AssocId`] fields through the map,
+//! so referenced types must be copied before the types that reference them.
+//!
+//! [`copy_all_with`] reports progress, and skips items that are already in the map, so a copy
+//! that was interrupted can be resumed from a [`Journal`].
+
+use core::{fmt, hash::Hash, marker::PhantomData};
+use std::{collections::HashMap, io::Write as _};
+
+use crate::{AssocId, BackingStorage, Create, List, boxed};
+
+/// Maps the ids of copied items of type `T` from the source storage to the destination storage.
+pub struct IdMap<T, From, To> {
+    /// The destination id of every source id.
+    ids: HashMap<From, To>,
+
+    /// Phantom data.
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T, From: Eq + Hash, To> IdMap<T, From, To> {
+    /// Create a new (empty) instance.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the destination id of the item with the source id, or [`None`] if it wasn't
+    /// copied.
+    pub fn get(&self, id: &AssocId<T, From>) -> Option<AssocId<T, To>>
+    where
+        To: Clone,
+    {
+        self.ids.get(id.as_raw()).cloned().map(AssocId::new)
+    }
+
+    /// Returns `true` if the item with the source id was copied.
+    pub fn contains(&self, id: &AssocId<T, From>) -> bool {
+        self.ids.contains_key(id.as_raw())
+    }
+
+    /// Records that the item with the source id `from` was copied to `to`.
+    pub fn insert(&mut self, from: AssocId<T, From>, to: AssocId<T, To>) {
+        self.ids.insert(from.into_raw(), to.into_raw());
+    }
+
+    /// Returns the number of copied items.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns `true` if no items were copied.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Iterates over the source and destination ids of every copied item.
+    pub fn iter(&self) -> impl Iterator<Item = (&From, &To)> {
+        self.ids.iter()
+    }
+}
+
+impl<T, RawId: Clone + Eq + Hash> IdMap<T, RawId, RawId> {
+    /// Rewrites every reference to a `T` in the item to its destination id.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the item references a `T` that wasn't copied.
+    pub fn remap<C: ReferencesMut<T, RawId> + ?Sized>(
+        &self,
+        item: &mut C,
+    ) -> Result<(), UnmappedId> {
+        for reference in item.references_mut() {
+            *reference = self.get(reference).ok_or(UnmappedId)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, From: Eq + Hash, To> Default for IdMap<T, From, To> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, From: fmt::Debug, To: fmt::Debug> fmt::Debug for IdMap<T, From, To> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdMap").field("ids", &self.ids).finish()
+    }
+}
+
+impl<T, From: Clone, To: Clone> Clone for IdMap<T, From, To> {
+    fn clone(&self) -> Self {
+        Self {
+            ids: self.ids.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, From: Eq + Hash, To> FromIterator<(From, To)> for IdMap<T, From, To> {
+    fn from_iter<I: IntoIterator<Item = (From, To)>>(iter: I) -> Self {
+        Self {
+            ids: iter.into_iter().collect(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, From: Eq + Hash, To> Extend<(From, To)> for IdMap<T, From, To> {
+    fn extend<I: IntoIterator<Item = (From, To)>>(&mut self, iter: I) {
+        self.ids.extend(iter);
+    }
+}
+
+/// A type with [`AssocId`] fields that reference items of type `T`, which can be rewritten.
+pub trait ReferencesMut<T: ?Sized, RawId> {
+    /// Returns the ids of every `T` that the item references.
+    fn references_mut(&mut self) -> Vec<&mut AssocId<T, RawId>>;
+}
+
+/// An item references an item that wasn't copied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnmappedId;
+
+impl fmt::Display for UnmappedId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "reference to an item that wasn't copied")
+    }
+}
+
+impl core::error::Error for UnmappedId {}
+
+/// Receives the progress of a copy.
+pub trait Progress<From, To> {
+    /// The error type that can be returned from [`Progress::copied`].
+    type Error;
+
+    /// Called once the items to copy have been listed. `total` includes items that were already
+    /// copied.
+    fn started(&mut self, total: usize) {
+        let _ = total;
+    }
+
+    /// Called after an item was copied. Returning an error stops the copy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the progress can't be recorded.
+    fn copied(&mut self, from: &From, to: &To) -> Result<(), Self::Error>;
+}
+
+/// Ignores progress.
+impl<From, To> Progress<From, To> for () {
+    type Error = core::convert::Infallible;
+
+    fn copied(&mut self, _from: &From, _to: &To) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Records the ids of copied items in a file, so an interrupted copy can be resumed.
+///
+/// Every line holds the source and destination id of an item, separated by a tab, so ids must not
+/// contain tabs or newlines. An item that was created but not recorded yet when the copy was
+/// interrupted is copied again when it is resumed, and so is an item whose line was only partly
+/// written (a last line without a newline), which is removed when the journal is opened.
+#[derive(Debug)]
+pub struct Journal {
+    /// The file that ids are appended to.
+    file: std::fs::File,
+}
+
+impl Journal {
+    /// Opens (or creates) a journal. Returns the journal, and the ids it already holds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be opened, read, or truncated, or it holds an invalid id.
+    pub fn open<T, From, To>(
+        path: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<(Self, IdMap<T, From, To>)>
+    where
+        From: core::str::FromStr + Eq + Hash,
+        To: core::str::FromStr,
+    {
+        let path = path.as_ref();
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+
+        // A crash while appending can leave a partial last line, which is dropped (its item is
+        // copied again).
+        let contents = std::fs::read_to_string(path)?;
+        let complete = contents.rfind('\n').map_or(0, |end| end + 1);
+        if complete < contents.len() {
+            file.set_len(complete as u64)?;
+        }
+
+        let ids = contents[..complete]
+            .lines()
+            .map(|line| {
+                let (from, to) = line.split_once('\t')?;
+                Some((from.parse().ok()?, to.parse().ok()?))
+            })
+            .collect::<Option<IdMap<T, From, To>>>()
+            .ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid journal entry")
+            })?;
+
+        Ok((Self { file }, ids))
+    }
+}
+
+impl<From: fmt::Display, To: fmt::Display> Progress<From, To> for Journal {
+    type Error = std::io::Error;
+
+    fn copied(&mut self, from: &From, to: &To) -> Result<(), Self::Error> {
+        // A single write, so that the line is either written completely or cut short, but not
+        // interleaved with the next one.
+        self.file.write_all(format!("{from}\t{to}\n").as_bytes())?;
+        self.file.sync_data()
+    }
+}
+
+/// Errors that can be returned when copying items.
+#[derive(Debug)]
+pub enum MigrateError<L, C, P> {
+    /// Listing the items in the source storage failed.
+    List(L),
+
+    /// Creating an item in the destination storage failed.
+    Create(C),
+
+    /// Recording the progress failed.
+    Progress(P),
+
+    /// An item references an item that wasn't copied.
+    Unmapped(UnmappedId),
+}
+
+impl<L: fmt::Display, C: fmt::Display, P: fmt::Display> fmt::Display for MigrateError<L, C, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::List(e) => write!(f, "list error: {e}"),
+            Self::Create(e) => write!(f, "create error: {e}"),
+            Self::Progress(e) => write!(f, "progress error: {e}"),
+            Self::Unmapped(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl<L, C, P> core::error::Error for MigrateError<L, C, P>
+where
+    L: fmt::Debug + fmt::Display,
+    C: fmt::Debug + fmt::Display,
+    P: fmt::Debug + fmt::Display,
+{
+}
+
+/// The error type of a copy of `T`s from `F` to `D`.
+pub type CopyError<T, F, D, P> = MigrateError<<T as List<F>>::Error, <T as Create<D>>::Error, P>;
+
+/// Copies every `T` from `from` to `to`. Returns the ids of the copies.
+///
+/// # Errors
+///
+/// Returns an error if listing or creating an item fails.
+pub async fn copy_all<T, F, D>(
+    from: &F,
+    to: &D,
+) -> Result<IdMap<T, F::RawId, D::RawId>, CopyError<T, F, D, core::convert::Infallible>>
+where
+    T: List<F> + Create<D>,
+    F: BackingStorage + Sync,
+    D: BackingStorage + Sync,
+    F::RawId: Clone + Eq + Hash,
+{
+    let mut ids = IdMap::new();
+    copy_all_with(from, to, &mut ids, &mut (), |_| Ok(())).await?;
+    Ok(ids)
+}
+
+/// Copies every `T` that isn't in `ids` yet from `from` to `to`, after passing it to `remap`.
+/// Records the ids of the copies in `ids` and `progress`, and returns the number of copied items.
+///
+/// # Errors
+///
+/// Returns an error if listing or creating an item, `remap`, or recording the progress fails.
+/// Items that were copied before the error are in `ids`.
+pub async fn copy_all_with<T, F, D, P>(
+    from: &F,
+    to: &D,
+    ids: &mut IdMap<T, F::RawId, D::RawId>,
+    progress: &mut P,
+    mut remap: impl FnMut(&mut T) -> Result<(), UnmappedId>,
+) -> Result<usize, CopyError<T, F, D, P::Error>>
+where
+    T: List<F> + Create<D>,
+    F: BackingStorage + Sync,
+    D: BackingStorage + Sync,
+    F::RawId: Clone + Eq + Hash,
+    P: Progress<F::RawId, D::RawId>,
+{
+    let items = boxed(<T as List<F>>::list(from))
+        .await
+        .map_err(MigrateError::List)?;
+    progress.started(items.len());
+
+    let mut copied = 0;
+    for (id, mut item) in items {
+        if ids.contains(&id) {
+            continue;
+        }
+
+        remap(&mut item).map_err(MigrateError::Unmapped)?;
+        let new_id = boxed(<T as Create<D>>::create(&item, to))
+            .await
+            .map_err(MigrateError::Create)?;
+
+        progress
+            .copied(id.as_raw(), new_id.as_raw())
+            .map_err(MigrateError::Progress)?;
+        ids.insert(id, new_id);
+        copied += 1;
+    }
+
+    Ok(copied)
+}
+
+/// Copies every `C` that isn't in `ids` yet from `from` to `to`, rewriting its references to `T`s
+/// through `references` (the ids returned when the `T`s were copied). Returns the number of copied
+/// items.
+///
+/// # Errors
+///
+/// Returns an error if listing or creating an item, or recording the progress fails, or a `C`
+/// references a `T` that wasn't copied.
+pub async fn copy_dependents<C, T, F, D, P>(
+    from: &F,
+    to: &D,
+    references: &IdMap<T, F::RawId, F::RawId>,
+    ids: &mut IdMap<C, F::RawId, F::RawId>,
+    progress: &mut P,
+) -> Result<usize, CopyError<C, F, D, P::Error>>
+where
+    C: List<F> + Create<D> + ReferencesMut<T, F::RawId>,
+    F: BackingStorage + Sync,
+    D: BackingStorage<RawId = F::RawId> + Sync,
+    F::RawId: Clone + Eq + Hash,
+    P: Progress<F::RawId, F::RawId>,
+{
+    copy_all_with(from, to, ids, progress, |item| references.remap(item)).await
+}
@@ -0,0 +1,209 @@
+//! Typed, prefixed string encoding for [`AssocId`]s (e.g. `cookie_42`).
+//!
+//! Types declare a prefix with [`IdPrefix`]. Wrapping an id in [`Prefixed`] gives it `Display`,
+//! `FromStr`, and (with the `serde` feature) serde impls that emit `prefix_<raw>`, and reject ids
+//! of another type when parsing. Fields can use the prefixed encoding without the wrapper through
+//! `#[serde(with = "storage_noodle_traits::prefixed")]`.
+//!
+//! This is the way to log, display, and serialize ids that should be told apart by type:
+//! `id.prefixed()` displays as `cookie_42`. [`AssocId`] itself keeps the raw encoding, because its
+//! `Display` and serde impls cover every `T`, and a prefixed impl for `T: IdPrefix` would overlap
+//! with them (Rust has no specialization to pick the more specific one). It has no `FromStr`
+//! either, so that parsing always round-trips with `Display`.
+//!
+//! Prefixes can't contain the separator (which is checked at compile time), so the prefix always
+//! ends at the first separator and raw ids can contain anything. Without this, `cookie_jar_7`
+//! could be a `cookie_jar` id, or a `cookie` id with the raw id `jar_7`.
+
+use core::{fmt, str::FromStr};
+
+use crate::AssocId;
+
+/// The separator between the prefix and the raw id.
+pub const SEPARATOR: char = '_';
+
+/// A type whose ids are prefixed when encoded with [`Prefixed`].
+pub trait IdPrefix {
+    /// The prefix, without the separator. Should be unique per type, and can't contain the
+    /// separator.
+    const PREFIX: &'static str;
+}
+
+/// Returns the prefix of `T`, failing to compile if it contains the separator.
+const fn prefix<T: ?Sized + IdPrefix>() -> &'static str {
+    const {
+        let bytes = T::PREFIX.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            assert!(
+                bytes[i] as char != SEPARATOR,
+                "id prefixes can't contain the separator"
+            );
+            i += 1;
+        }
+    }
+    T::PREFIX
+}
+
+/// An [`AssocId`] that is encoded as `prefix_<raw>`.
+pub struct Prefixed<T: ?Sized, RawId>(pub AssocId<T, RawId>);
+
+impl<T: ?Sized + IdPrefix, RawId> AssocId<T, RawId> {
+    /// Wraps the id in [`Prefixed`], so that it is displayed and serialized with the prefix of
+    /// `T` (e.g. `cookie_42`).
+    pub const fn prefixed(self) -> Prefixed<T, RawId> {
+        Prefixed(self)
+    }
+}
+
+impl<T: ?Sized, RawId: fmt::Debug> fmt::Debug for Prefixed<T, RawId> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Prefixed").field(&self.0).finish()
+    }
+}
+
+impl<T: ?Sized, RawId: Clone> Clone for Prefixed<T, RawId> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized, RawId: Copy> Copy for Prefixed<T, RawId> {}
+
+impl<T: ?Sized, RawId: PartialEq> PartialEq for Prefixed<T, RawId> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: ?Sized, RawId: Eq> Eq for Prefixed<T, RawId> {}
+
+impl<T: ?Sized, RawId: core::hash::Hash> core::hash::Hash for Prefixed<T, RawId> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<T: ?Sized, RawId> From<AssocId<T, RawId>> for Prefixed<T, RawId> {
+    fn from(value: AssocId<T, RawId>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: ?Sized, RawId> From<Prefixed<T, RawId>> for AssocId<T, RawId> {
+    fn from(value: Prefixed<T, RawId>) -> Self {
+        value.0
+    }
+}
+
+impl<T: ?Sized + IdPrefix, RawId: fmt::Display> fmt::Display for Prefixed<T, RawId> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{SEPARATOR}{}", prefix::<T>(), self.0.as_raw())
+    }
+}
+
+impl<T: ?Sized + IdPrefix, RawId: FromStr> FromStr for Prefixed<T, RawId> {
+    type Err = ParsePrefixedError<RawId::Err>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = s
+            .strip_prefix(prefix::<T>())
+            .and_then(|rest| rest.strip_prefix(SEPARATOR))
+            .ok_or_else(|| ParsePrefixedError::WrongPrefix {
+                expected: prefix::<T>(),
+                found: s.to_string(),
+            })?;
+        if raw.is_empty() {
+            return Err(ParsePrefixedError::EmptyRaw);
+        }
+
+        raw.parse()
+            .map(|raw| Self(AssocId::new(raw)))
+            .map_err(ParsePrefixedError::Raw)
+    }
+}
+
+/// Errors that can be returned when parsing a [`Prefixed`] id.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParsePrefixedError<E> {
+    /// The id doesn't start with the prefix of the type (it may be an id of another type).
+    WrongPrefix {
+        /// The prefix of the type.
+        expected: &'static str,
+
+        /// The id that was parsed.
+        found: String,
+    },
+
+    /// The id has nothing after the prefix.
+    EmptyRaw,
+
+    /// The raw id couldn't be parsed.
+    Raw(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ParsePrefixedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongPrefix { expected, found } => {
+                write!(
+                    f,
+                    "expected an id starting with `{expected}{SEPARATOR}`, found `{found}`"
+                )
+            }
+            Self::EmptyRaw => write!(f, "the raw id is empty"),
+            Self::Raw(e) => write!(f, "invalid raw id: {e}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> core::error::Error for ParsePrefixedError<E> {}
+
+#[cfg(feature = "serde")]
+impl<T: ?Sized + IdPrefix, RawId: fmt::Display> serde::Serialize for Prefixed<T, RawId> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: ?Sized + IdPrefix, RawId: FromStr> serde::Deserialize<'de> for Prefixed<T, RawId>
+where
+    RawId::Err: fmt::Display,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serializes an [`AssocId`] as `prefix_<raw>`, for use with `#[serde(with = "...")]`.
+///
+/// # Errors
+///
+/// Returns an error if the serializer fails.
+#[cfg(feature = "serde")]
+pub fn serialize<T, RawId, S>(id: &AssocId<T, RawId>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + IdPrefix,
+    RawId: fmt::Display,
+    S: serde::Serializer,
+{
+    serializer.collect_str(&format_args!("{}{SEPARATOR}{}", prefix::<T>(), id.as_raw()))
+}
+
+/// Deserializes an [`AssocId`] from `prefix_<raw>`, for use with `#[serde(with = "...")]`.
+///
+/// # Errors
+///
+/// Returns an error if the id has the wrong prefix, or the raw id is invalid.
+#[cfg(feature = "serde")]
+pub fn deserialize<'de, T, RawId, D>(deserializer: D) -> Result<AssocId<T, RawId>, D::Error>
+where
+    T: ?Sized + IdPrefix,
+    RawId: FromStr,
+    RawId::Err: fmt::Display,
+    D: serde::Deserializer<'de>,
+{
+    <Prefixed<T, RawId> as serde::Deserialize>::deserialize(deserializer).map(|id| id.0)
+}
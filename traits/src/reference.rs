@@ -0,0 +1,144 @@
+//! Lazily loaded references to other items.
+
+use core::{fmt, ops::Deref};
+use std::sync::OnceLock;
+
+use crate::{AssocId, BackingStorage, Read};
+
+/// A reference to an item, that is stored as the raw id (like an [`AssocId`]), and caches the item
+/// once it has been loaded.
+///
+/// Equality, ordering, hashing, and (de)serialization only consider the id.
+pub struct Ref<T, RawId> {
+    /// The id of the referenced item.
+    id: AssocId<T, RawId>,
+
+    /// The referenced item (once it has been loaded).
+    item: OnceLock<T>,
+}
+
+impl<T, RawId> Ref<T, RawId> {
+    /// Create a new, unloaded reference.
+    pub const fn new(id: AssocId<T, RawId>) -> Self {
+        Self {
+            id,
+            item: OnceLock::new(),
+        }
+    }
+
+    /// Create a reference that is already loaded.
+    pub fn loaded(id: AssocId<T, RawId>, item: T) -> Self {
+        Self {
+            id,
+            item: OnceLock::from(item),
+        }
+    }
+
+    /// Get the id of the referenced item.
+    pub const fn id(&self) -> &AssocId<T, RawId> {
+        &self.id
+    }
+
+    /// Get the id of the referenced item, dropping the cached item.
+    pub fn into_id(self) -> AssocId<T, RawId> {
+        self.id
+    }
+
+    /// Get the referenced item, if it has been loaded.
+    pub fn get(&self) -> Option<&T> {
+        self.item.get()
+    }
+
+    /// Get the referenced item, reading it from a backing storage if it hasn't been loaded yet.
+    /// Will return [`None`] if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the item couldn't be read.
+    pub async fn load<S>(
+        &self,
+        storage: impl Deref<Target = S> + Send,
+    ) -> Result<Option<&T>, T::Error>
+    where
+        S: BackingStorage<RawId = RawId>,
+        T: Read<S>,
+        RawId: Sync,
+    {
+        if let Some(item) = self.item.get() {
+            return Ok(Some(item));
+        }
+
+        let Some(item) = self.id.fetch(storage).await? else {
+            return Ok(None);
+        };
+
+        // Another load may have finished first, both read the same item.
+        Ok(Some(self.item.get_or_init(|| item)))
+    }
+}
+
+impl<T, RawId> From<AssocId<T, RawId>> for Ref<T, RawId> {
+    fn from(value: AssocId<T, RawId>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: fmt::Debug, RawId: fmt::Debug> fmt::Debug for Ref<T, RawId> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ref")
+            .field("id", &self.id)
+            .field("item", &self.item.get())
+            .finish()
+    }
+}
+
+impl<T: Clone, RawId: Clone> Clone for Ref<T, RawId> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            item: self.item.clone(),
+        }
+    }
+}
+
+impl<T, RawId: PartialEq> PartialEq for Ref<T, RawId> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T, RawId: Eq> Eq for Ref<T, RawId> {}
+
+impl<T, RawId: PartialOrd> PartialOrd for Ref<T, RawId> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        self.id.partial_cmp(&other.id)
+    }
+}
+
+impl<T, RawId: Ord> Ord for Ref<T, RawId> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl<T, RawId: core::hash::Hash> core::hash::Hash for Ref<T, RawId> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// Serialized as the raw id.
+#[cfg(feature = "serde")]
+impl<T, RawId: serde::Serialize> serde::Serialize for Ref<T, RawId> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.id.serialize(serializer)
+    }
+}
+
+/// Deserialized from the raw id, unloaded.
+#[cfg(feature = "serde")]
+impl<'de, T, RawId: serde::Deserialize<'de>> serde::Deserialize<'de> for Ref<T, RawId> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        AssocId::deserialize(deserializer).map(Self::new)
+    }
+}
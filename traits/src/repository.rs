@@ -0,0 +1,119 @@
+//! Handles that bind an item type to a backing storage.
+
+use core::{fmt, marker::PhantomData};
+
+use crate::{AssocId, BackingStorage, Create, Delete, Items, List, Read, Update};
+
+/// A backing storage for items of type `T`.
+///
+/// Services can depend on a `Repository<Cookie, S>` instead of a backing storage and the CRUD
+/// traits, and tests can hand them a repository over a different backing storage.
+pub struct Repository<T, S> {
+    /// The backing storage.
+    storage: S,
+
+    /// Phantom data.
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T, S> Repository<T, S> {
+    /// Create a new instance.
+    pub const fn new(storage: S) -> Self {
+        Self {
+            storage,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Get a reference to the backing storage.
+    pub const fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Get the backing storage.
+    pub fn into_storage(self) -> S {
+        self.storage
+    }
+}
+
+impl<T, S: BackingStorage + Sync> Repository<T, S> {
+    /// Creates a new item. Returns the Id of the new item.
+    ///
+    /// Shorthand for [`Create::create`].
+    pub fn create<'a>(
+        &'a self,
+        item: &'a T,
+    ) -> impl Future<Output = Result<AssocId<T, S::RawId>, T::Error>> + Send
+    where
+        T: Create<S>,
+    {
+        <T as Create<S>>::create(item, &self.storage)
+    }
+
+    /// Reads an item. Will return [`None`] if it doesn't exist.
+    ///
+    /// Shorthand for [`Read::read`].
+    pub fn read(
+        &self,
+        id: &AssocId<T, S::RawId>,
+    ) -> impl Future<Output = Result<Option<T>, T::Error>> + Send
+    where
+        T: Read<S>,
+        S::RawId: Sync,
+    {
+        <T as Read<S>>::read(&self.storage, id)
+    }
+
+    /// Replaces the item with the id. Will return [`None`] if it doesn't exist.
+    ///
+    /// Shorthand for [`Update::update`].
+    pub fn update<'a>(
+        &'a self,
+        id: &'a AssocId<T, S::RawId>,
+        item: &'a T,
+    ) -> impl Future<Output = Result<Option<()>, T::Error>> + Send
+    where
+        T: Update<S>,
+        S::RawId: Sync,
+    {
+        <T as Update<S>>::update(item, &self.storage, id)
+    }
+
+    /// Deletes the item with the id. Will return [`None`] if it doesn't exist.
+    ///
+    /// Shorthand for [`Delete::delete`].
+    pub fn delete(
+        &self,
+        id: &AssocId<T, S::RawId>,
+    ) -> impl Future<Output = Result<Option<()>, T::Error>> + Send
+    where
+        T: Delete<S>,
+        S::RawId: Sync,
+    {
+        <T as Delete<S>>::delete(&self.storage, id)
+    }
+
+    /// Reads every item.
+    ///
+    /// Shorthand for [`List::list`].
+    pub fn list(&self) -> impl Future<Output = Result<Items<T, S::RawId>, T::Error>> + Send
+    where
+        T: List<S>,
+    {
+        <T as List<S>>::list(&self.storage)
+    }
+}
+
+impl<T, S: fmt::Debug> fmt::Debug for Repository<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Repository")
+            .field("storage", &self.storage)
+            .finish()
+    }
+}
+
+impl<T, S: Clone> Clone for Repository<T, S> {
+    fn clone(&self) -> Self {
+        Self::new(self.storage.clone())
+    }
+}
@@ -1,15 +1,19 @@
 //! Implement [`sqlx`] traits around the [`crate::AssocId<T, RawId>`] wrapper.
 
-impl<T, RawId, DB: sqlx::Database> sqlx::Type<DB> for crate::AssocId<T, RawId>
+impl<T: ?Sized, RawId, DB: sqlx::Database> sqlx::Type<DB> for crate::AssocId<T, RawId>
 where
     RawId: sqlx::Type<DB>,
 {
     fn type_info() -> <DB as sqlx::Database>::TypeInfo {
         RawId::type_info()
     }
+
+    fn compatible(ty: &<DB as sqlx::Database>::TypeInfo) -> bool {
+        RawId::compatible(ty)
+    }
 }
 
-impl<'a, T, RawId, DB: sqlx::Database> sqlx::Encode<'a, DB> for crate::AssocId<T, RawId>
+impl<'a, T: ?Sized, RawId, DB: sqlx::Database> sqlx::Encode<'a, DB> for crate::AssocId<T, RawId>
 where
     RawId: sqlx::Encode<'a, DB>,
 {
@@ -19,9 +23,17 @@ where
     ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
         RawId::encode_by_ref(&self.inner, buf)
     }
+
+    fn produces(&self) -> Option<<DB as sqlx::Database>::TypeInfo> {
+        self.inner.produces()
+    }
+
+    fn size_hint(&self) -> usize {
+        self.inner.size_hint()
+    }
 }
 
-impl<'a, T, RawId, DB: sqlx::Database> sqlx::Decode<'a, DB> for crate::AssocId<T, RawId>
+impl<'a, T: ?Sized, RawId, DB: sqlx::Database> sqlx::Decode<'a, DB> for crate::AssocId<T, RawId>
 where
     RawId: sqlx::Decode<'a, DB>,
 {
@@ -33,7 +45,61 @@ where
 }
 
 #[cfg(feature = "sqlx_pg_array")]
-impl<T, RawId> sqlx::postgres::PgHasArrayType for crate::AssocId<T, RawId>
+impl<T: ?Sized, RawId> sqlx::postgres::PgHasArrayType for crate::AssocId<T, RawId>
+where
+    RawId: sqlx::postgres::PgHasArrayType,
+{
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        RawId::array_type_info()
+    }
+}
+
+impl<T, RawId, DB: sqlx::Database> sqlx::Type<DB> for crate::reference::Ref<T, RawId>
+where
+    RawId: sqlx::Type<DB>,
+{
+    fn type_info() -> <DB as sqlx::Database>::TypeInfo {
+        RawId::type_info()
+    }
+
+    fn compatible(ty: &<DB as sqlx::Database>::TypeInfo) -> bool {
+        RawId::compatible(ty)
+    }
+}
+
+impl<'a, T, RawId, DB: sqlx::Database> sqlx::Encode<'a, DB> for crate::reference::Ref<T, RawId>
+where
+    RawId: sqlx::Encode<'a, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as sqlx::Database>::ArgumentBuffer<'a>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        self.id().encode_by_ref(buf)
+    }
+
+    fn produces(&self) -> Option<<DB as sqlx::Database>::TypeInfo> {
+        <crate::AssocId<T, RawId> as sqlx::Encode<'a, DB>>::produces(self.id())
+    }
+
+    fn size_hint(&self) -> usize {
+        <crate::AssocId<T, RawId> as sqlx::Encode<'a, DB>>::size_hint(self.id())
+    }
+}
+
+impl<'a, T, RawId, DB: sqlx::Database> sqlx::Decode<'a, DB> for crate::reference::Ref<T, RawId>
+where
+    RawId: sqlx::Decode<'a, DB>,
+{
+    fn decode(
+        value: <DB as sqlx::Database>::ValueRef<'a>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        Ok(Self::new(crate::AssocId::decode(value)?))
+    }
+}
+
+#[cfg(feature = "sqlx_pg_array")]
+impl<T, RawId> sqlx::postgres::PgHasArrayType for crate::reference::Ref<T, RawId>
 where
     RawId: sqlx::postgres::PgHasArrayType,
 {
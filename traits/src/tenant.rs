@@ -0,0 +1,162 @@
+//! Multi-tenant namespacing for backing storages.
+//!
+//! Types implement the CRUD traits for a [`TenantScoped`] storage either directly, or through the
+//! `Scoped*` traits in this module. The latter exist for backend crates that can't implement
+//! `Create<TenantScoped<...>>` for foreign item types (e.g. `storage_noodle_object_s3`).
+
+use core::ops::Deref;
+
+use crate::{AssocId, BackingStorage, Create, Delete, Read, Update};
+
+/// A [`BackingStorage`] wrapper that scopes every item to a single tenant.
+///
+/// Backends decide how the tenant is stored (an implicit column for SQL, a key prefix for S3), but
+/// items are only ever visible through a [`TenantScoped`] with the same tenant that created them.
+#[derive(Debug, Clone)]
+pub struct TenantScoped<S, TenantId = String> {
+    /// The wrapped backing storage.
+    pub inner: S,
+
+    /// The tenant that all items are scoped to.
+    pub tenant: TenantId,
+}
+
+impl<S, TenantId> TenantScoped<S, TenantId> {
+    /// Create a new instance.
+    pub const fn new(inner: S, tenant: TenantId) -> Self {
+        Self { inner, tenant }
+    }
+}
+
+impl<S: BackingStorage, TenantId> BackingStorage for TenantScoped<S, TenantId> {
+    type RawId = S::RawId;
+}
+
+/// Tenant scoped version of [`Create`]. Implementing it implements [`Create`] for
+/// [`TenantScoped<S, TenantId>`].
+pub trait ScopedCreate<S: BackingStorage, TenantId> {
+    /// The error type that can be returned from [`ScopedCreate::create_scoped`].
+    type Error;
+
+    /// Creates a new item owned by `tenant`. Returns the Id of the new item.
+    fn create_scoped<'a>(
+        &'a self,
+        storage: &'a S,
+        tenant: &'a TenantId,
+    ) -> impl Future<Output = Result<AssocId<Self, S::RawId>, Self::Error>> + Send;
+}
+
+/// Tenant scoped version of [`Read`]. Implementing it implements [`Read`] for
+/// [`TenantScoped<S, TenantId>`].
+pub trait ScopedRead<S: BackingStorage, TenantId>: Sized {
+    /// The error type that can be returned from [`ScopedRead::read_scoped`].
+    type Error;
+
+    /// Reads an item owned by `tenant`. Returns the item.
+    fn read_scoped<'a>(
+        storage: &'a S,
+        tenant: &'a TenantId,
+        id: &'a AssocId<Self, S::RawId>,
+    ) -> impl Future<Output = Result<Option<Self>, Self::Error>> + Send;
+}
+
+/// Tenant scoped version of [`Update`]. Implementing it implements [`Update`] for
+/// [`TenantScoped<S, TenantId>`].
+pub trait ScopedUpdate<S: BackingStorage, TenantId> {
+    /// The error type that can be returned from [`ScopedUpdate::update_scoped`].
+    type Error;
+
+    /// Updates an item owned by `tenant`. Will return [`None`] if the item doesn't exist.
+    fn update_scoped<'a>(
+        &'a self,
+        storage: &'a S,
+        tenant: &'a TenantId,
+        id: &'a AssocId<Self, S::RawId>,
+    ) -> impl Future<Output = Result<Option<()>, Self::Error>> + Send;
+}
+
+/// Tenant scoped version of [`Delete`]. Implementing it implements [`Delete`] for
+/// [`TenantScoped<S, TenantId>`].
+pub trait ScopedDelete<S: BackingStorage, TenantId>: Sized {
+    /// The error type that can be returned from [`ScopedDelete::delete_scoped`].
+    type Error;
+
+    /// Deletes an item owned by `tenant`. Will return [`None`] if the item doesn't exist.
+    fn delete_scoped<'a>(
+        storage: &'a S,
+        tenant: &'a TenantId,
+        id: &'a AssocId<Self, S::RawId>,
+    ) -> impl Future<Output = Result<Option<()>, Self::Error>> + Send;
+}
+
+impl<T, S, TenantId> Create<TenantScoped<S, TenantId>> for T
+where
+    T: ScopedCreate<S, TenantId> + Sync,
+    S: BackingStorage + Sync,
+    TenantId: Sync,
+{
+    type Error = T::Error;
+
+    async fn create<'a>(
+        &'a self,
+        storage: impl Deref<Target = TenantScoped<S, TenantId>> + 'a + Send,
+    ) -> Result<AssocId<Self, S::RawId>, Self::Error> {
+        let TenantScoped { inner, tenant } = &*storage;
+        self.create_scoped(inner, tenant).await
+    }
+}
+
+impl<T, S, TenantId> Read<TenantScoped<S, TenantId>> for T
+where
+    T: ScopedRead<S, TenantId>,
+    S: BackingStorage + Sync,
+    S::RawId: Sync,
+    TenantId: Sync,
+{
+    type Error = T::Error;
+
+    async fn read(
+        storage: impl Deref<Target = TenantScoped<S, TenantId>> + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> Result<Option<Self>, Self::Error> {
+        let TenantScoped { inner, tenant } = &*storage;
+        T::read_scoped(inner, tenant, &id).await
+    }
+}
+
+impl<T, S, TenantId> Update<TenantScoped<S, TenantId>> for T
+where
+    T: ScopedUpdate<S, TenantId> + Sync,
+    S: BackingStorage + Sync,
+    S::RawId: Sync,
+    TenantId: Sync,
+{
+    type Error = T::Error;
+
+    async fn update<'a>(
+        &'a self,
+        storage: impl Deref<Target = TenantScoped<S, TenantId>> + 'a + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> Result<Option<()>, Self::Error> {
+        let TenantScoped { inner, tenant } = &*storage;
+        self.update_scoped(inner, tenant, &id).await
+    }
+}
+
+impl<T, S, TenantId> Delete<TenantScoped<S, TenantId>> for T
+where
+    T: ScopedDelete<S, TenantId>,
+    S: BackingStorage + Sync,
+    S::RawId: Sync,
+    TenantId: Sync,
+{
+    type Error = T::Error;
+
+    async fn delete(
+        storage: impl Deref<Target = TenantScoped<S, TenantId>> + Send,
+        id: impl Deref<Target = AssocId<Self, S::RawId>> + Send,
+    ) -> Result<Option<()>, Self::Error> {
+        let TenantScoped { inner, tenant } = &*storage;
+        T::delete_scoped(inner, tenant, &id).await
+    }
+}
@@ -0,0 +1,132 @@
+//! Integration test for backend-independent types.
+
+use storage_noodle_traits::{
+    AssocId,
+    prefixed::{IdPrefix, ParsePrefixedError, Prefixed},
+};
+
+#[test]
+fn prefixed_ids() {
+    // Ids are displayed with the prefix of their type.
+    let cookie_id: Prefixed<Cookie, u32> = AssocId::new(42).into();
+    assert_eq!(cookie_id.to_string(), "cookie_42");
+
+    // And parsed back.
+    let parsed: Prefixed<Cookie, u32> = "cookie_42".parse().unwrap();
+    assert_eq!(parsed, cookie_id);
+
+    // Ids can be prefixed where they are logged, while `AssocId` keeps the raw encoding.
+    let id: AssocId<Cookie, u32> = AssocId::new(42);
+    assert_eq!(format!("{id} {}", id.prefixed()), "42 cookie_42");
+
+    // Ids of another type are rejected.
+    let error = "recipe_42".parse::<Prefixed<Cookie, u32>>().unwrap_err();
+    assert_eq!(
+        error,
+        ParsePrefixedError::WrongPrefix {
+            expected: "cookie",
+            found: "recipe_42".to_string(),
+        }
+    );
+    assert!(matches!(
+        "cookie_abc".parse::<Prefixed<Cookie, u32>>(),
+        Err(ParsePrefixedError::Raw(_))
+    ));
+    assert_eq!(
+        "cookie_".parse::<Prefixed<Cookie, String>>(),
+        Err(ParsePrefixedError::EmptyRaw)
+    );
+
+    // Prefixes can't overlap (they can't contain the separator), so raw ids can contain anything.
+    let jar_id: Prefixed<CookieJar, u32> = AssocId::new(42).into();
+    assert_eq!(jar_id.to_string(), "cookiejar_42");
+    assert!(matches!(
+        "cookiejar_42".parse::<Prefixed<Cookie, u32>>(),
+        Err(ParsePrefixedError::WrongPrefix { .. })
+    ));
+    let parsed: Prefixed<Cookie, String> = "cookie_jar_x".parse().unwrap();
+    assert_eq!(parsed.0.as_raw(), "jar_x");
+    assert_eq!(parsed.to_string(), "cookie_jar_x");
+
+    // Fields can be (de)serialized with the prefix.
+    let cookie = Cookie {
+        recipe: AssocId::new(7),
+    };
+    let json = serde_json::to_string(&cookie).unwrap();
+    assert_eq!(json, r#"{"recipe":"recipe_7"}"#);
+    assert_eq!(serde_json::from_str::<Cookie>(&json).unwrap(), cookie);
+    assert!(serde_json::from_str::<Cookie>(r#"{"recipe":"cookie_7"}"#).is_err());
+
+    // And so can the wrapper.
+    let json = serde_json::to_string(&cookie_id).unwrap();
+    assert_eq!(json, r#""cookie_42""#);
+    assert_eq!(
+        serde_json::from_str::<Prefixed<Cookie, u32>>(&json).unwrap(),
+        cookie_id
+    );
+}
+
+#[test]
+fn assoc_id_impls() {
+    // `NotSync` implements nothing, so none of these bounds come from the item type.
+    let id: AssocId<NotSync, u32> = 42.into();
+    let copy = id;
+    assert_eq!(id.clone(), copy);
+    assert!(AssocId::<NotSync, u32>::new(1) < id);
+    assert_eq!(format!("{id} {id:?}"), "42 AssocId(42)");
+
+    // Ids can be used as map keys.
+    let mut names = std::collections::HashMap::new();
+    names.insert(id, "forty two");
+    assert_eq!(names[&AssocId::new(42)], "forty two");
+
+    // Ids are `Send` and `Sync` regardless of the item type.
+    assert_send_sync(&id);
+
+    // Conversions.
+    let recipe_id: AssocId<Recipe, u32> = id.cast();
+    assert_eq!(recipe_id.into_raw(), 42);
+    let raw: u32 = recipe_id.into();
+    assert_eq!(raw, 42);
+    let raw: String = AssocId::<Recipe, String>::new("shortbread".to_string()).into();
+    assert_eq!(raw, "shortbread");
+
+    // Ids (de)serialize as the raw id.
+    assert_eq!(serde_json::to_string(&id).unwrap(), "42");
+    assert_eq!(
+        serde_json::from_str::<AssocId<NotSync, u32>>("42").unwrap(),
+        id
+    );
+}
+
+/// Only compiles if `T` is `Send` and `Sync`.
+const fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+/// A type that isn't `Sync`, and doesn't implement any traits.
+struct NotSync {
+    _cell: core::cell::Cell<u8>,
+}
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct Cookie {
+    #[serde(with = "storage_noodle_traits::prefixed")]
+    recipe: AssocId<Recipe, u32>,
+}
+
+impl IdPrefix for Cookie {
+    const PREFIX: &'static str = "cookie";
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct CookieJar;
+
+impl IdPrefix for CookieJar {
+    const PREFIX: &'static str = "cookiejar";
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Recipe;
+
+impl IdPrefix for Recipe {
+    const PREFIX: &'static str = "recipe";
+}